@@ -1,10 +1,12 @@
 use std::env::current_dir;
 use std::fs::create_dir_all;
 
-use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+use cosmwasm_schema::{export_schema, export_schema_with_title, remove_schemas, schema_for};
 
-use cw20_pot::msg::{CountResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
-use cw20_pot::state::State;
+use cw20_pot::msg::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, PotCyclesResponse, PotResponse,
+    QueryMsg, StatsResponse, VestingStatusResponse,
+};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -15,6 +17,10 @@ fn main() {
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
-    export_schema(&schema_for!(State), &out_dir);
-    export_schema(&schema_for!(CountResponse), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
+    export_schema(&schema_for!(ConfigResponse), &out_dir);
+    export_schema(&schema_for!(PotResponse), &out_dir);
+    export_schema(&schema_for!(PotCyclesResponse), &out_dir);
+    export_schema(&schema_for!(VestingStatusResponse), &out_dir);
+    export_schema_with_title(&mut schema_for!(StatsResponse), &out_dir, "StatsResponse");
 }
@@ -1,15 +1,27 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
-    Uint128, Uint64,
+    from_binary, to_binary, Addr, BankMsg, Binary, CosmosMsg, Deps, DepsMut, Empty, Env,
+    MessageInfo, Order, Reply, Response, StdError, StdResult, Storage, SubMsg, SubMsgResult,
+    Uint128, Uint64, WasmMsg,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
 use cw20::{Cw20Contract, Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_utils::{must_pay, nonpayable};
 
 use crate::error::ContractError;
-use crate::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, PotResponse, QueryMsg, ReceiveMsg};
-use crate::state::{save_pot, Config, Pot, CONFIG, POTS, POT_SEQ};
+use crate::migrations::{migrate_pots_from_v0_1, v0_1};
+use crate::msg::{
+    ConfigResponse, CreatePotMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, MilestoneMsg,
+    PotCyclesResponse, PotResponse, QueryMsg, ReceiptExecuteMsg, ReceiptExtension, ReceiptMintMsg,
+    ReceiveMsg, RecipientMsg, StatsResponse, UpdateConfigMsg, VestingStatusResponse,
+};
+use crate::state::{
+    save_pot, update_pot, Approval, Asset, Config, CreationFee, Milestone, OvershootPolicy,
+    PendingPayout, Pot, PotStatus, Recipient, Stats, Vesting, CONFIG, CONTRIBUTIONS, CONTRIBUTORS,
+    HOLDING_POTS, MIGRATED_POTS, PAYOUT_HEIGHTS, PAYOUT_SEQ, PENDING_PAYOUTS, POTS, POT_SEQ,
+    RECEIPT_SEQ, STATS, TOTAL_WEIGHT,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:cw20-pot";
@@ -26,92 +38,566 @@ pub fn instantiate(
 
     let owner = msg
         .admin
-        .and_then(|s| deps.api.addr_validate(s.as_str()).ok())
+        .map(|s| deps.api.addr_validate(s.as_str()))
+        .transpose()?
         .unwrap_or(info.sender);
 
+    let asset = match (msg.cw20_addr, msg.native_denom) {
+        (Some(cw20_addr), None) => Asset::Cw20(deps.api.addr_validate(&cw20_addr)?),
+        (None, Some(denom)) => Asset::Native(denom),
+        _ => return Err(ContractError::InvalidAsset {}),
+    };
+    if matches!(
+        (&asset, &msg.creation_fee),
+        (Asset::Native(_), Some(CreationFee::Cw20(_)))
+    ) {
+        return Err(ContractError::InvalidAsset {});
+    }
+
     let config = Config {
         owner: owner.clone(),
-        cw20_addr: deps.api.addr_validate(msg.cw20_addr.as_str())?,
+        asset: asset.clone(),
+        public_creation: msg.public_creation.unwrap_or(false),
+        creation_fee: msg.creation_fee,
+        pending_owner: None,
+        fallback_addr: msg
+            .fallback_addr
+            .map(|s| deps.api.addr_validate(s.as_str()))
+            .transpose()?,
+        receipt_contract: msg
+            .receipt_contract
+            .map(|s| deps.api.addr_validate(s.as_str()))
+            .transpose()?,
     };
 
     CONFIG.save(deps.storage, &config)?;
 
     POT_SEQ.save(deps.storage, &Uint64::new(0))?;
+    STATS.save(deps.storage, &Stats::default())?;
+    HOLDING_POTS.save(deps.storage, &0)?;
 
     Ok(Response::new()
         .add_attribute("method", "instantiate")
         .add_attribute("owner", owner)
-        .add_attribute("cw20_addr", msg.cw20_addr))
+        .add_attribute("asset", asset_attribute(&asset)))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::CreatePot {
-            target_addr,
-            threshold,
-        } => execute_create_pot(deps, info, target_addr, threshold),
-        ExecuteMsg::Receive(msg) => execute_receive(deps, info, msg),
+        ExecuteMsg::CreatePot(msg) => execute_create_pot(deps, info, msg),
+        ExecuteMsg::Receive(msg) => execute_receive(deps, env, info, msg),
+        ExecuteMsg::UpdateConfig(msg) => execute_update_config(deps, info, msg),
+        ExecuteMsg::TransferOwnership { new_owner } => {
+            execute_transfer_ownership(deps, info, new_owner)
+        }
+        ExecuteMsg::AcceptOwnership {} => execute_accept_ownership(deps, info),
+        ExecuteMsg::CancelPot { id } => execute_cancel_pot(deps, info, id),
+        ExecuteMsg::ApprovePayout { id } => execute_approve_payout(deps, env, info, id),
+        ExecuteMsg::RejectPayout { id } => execute_reject_payout(deps, info, id),
+        ExecuteMsg::DepositFrom { id, amount } => execute_deposit_from(deps, env, info, id, amount),
+        ExecuteMsg::Deposit { id } => execute_deposit(deps, env, info, id),
+        ExecuteMsg::ClaimVested { id } => execute_claim_vested(deps, env, info, id),
+        ExecuteMsg::RetryPayout { id } => execute_retry_payout(deps, info, id),
+    }
+}
+
+/// Validates an optional address update, clearing it gives `Some(None)`
+fn optional_addr_update(
+    deps: Deps,
+    field: &str,
+    addr: Option<String>,
+    clear: Option<bool>,
+) -> Result<Option<Option<Addr>>, ContractError> {
+    match (addr, clear.unwrap_or(false)) {
+        (Some(_), true) => Err(ContractError::ConflictingConfig {
+            field: field.to_string(),
+        }),
+        (None, true) => Ok(Some(None)),
+        (Some(addr), false) => Ok(Some(Some(deps.api.addr_validate(&addr)?))),
+        (None, false) => Ok(None),
+    }
+}
+
+pub fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    msg: UpdateConfigMsg,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if config.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut res = Response::new().add_attribute("action", "execute_update_config");
+
+    let asset = match (msg.cw20_addr, msg.native_denom) {
+        (None, None) => None,
+        (Some(cw20_addr), None) => Some(Asset::Cw20(deps.api.addr_validate(&cw20_addr)?)),
+        (None, Some(denom)) => Some(Asset::Native(denom)),
+        (Some(_), Some(_)) => return Err(ContractError::InvalidAsset {}),
+    };
+
+    if let Some(asset) = asset {
+        // switching asset would strand the balance of unfinished pots
+        if HOLDING_POTS.load(deps.storage)? > 0 {
+            return Err(ContractError::PotsHoldBalance {});
+        }
+        if matches!(
+            (&asset, &config.creation_fee),
+            (Asset::Native(_), Some(CreationFee::Cw20(_)))
+        ) {
+            return Err(ContractError::InvalidAsset {});
+        }
+        res = res.add_attribute("asset", asset_attribute(&asset));
+        config.asset = asset;
+    }
+
+    if let Some(public_creation) = msg.public_creation {
+        config.public_creation = public_creation;
+        res = res.add_attribute("public_creation", public_creation.to_string());
+    }
+
+    let fallback_addr = optional_addr_update(
+        deps.as_ref(),
+        "fallback_addr",
+        msg.fallback_addr,
+        msg.clear_fallback_addr,
+    )?;
+    if let Some(fallback_addr) = fallback_addr {
+        res = res.add_attribute("fallback_addr", optional_addr_attribute(&fallback_addr));
+        config.fallback_addr = fallback_addr;
+    }
+
+    let receipt_contract = optional_addr_update(
+        deps.as_ref(),
+        "receipt_contract",
+        msg.receipt_contract,
+        msg.clear_receipt_contract,
+    )?;
+    if let Some(receipt_contract) = receipt_contract {
+        res = res.add_attribute(
+            "receipt_contract",
+            optional_addr_attribute(&receipt_contract),
+        );
+        config.receipt_contract = receipt_contract;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(res)
+}
+
+fn optional_addr_attribute(addr: &Option<Addr>) -> String {
+    addr.as_ref()
+        .map_or_else(|| "none".to_string(), |addr| addr.to_string())
+}
+
+pub fn execute_transfer_ownership(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_owner: String,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if config.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.pending_owner = Some(deps.api.addr_validate(&new_owner)?);
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_transfer_ownership")
+        .add_attribute("pending_owner", new_owner))
+}
+
+pub fn execute_accept_ownership(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    let pending_owner = config
+        .pending_owner
+        .take()
+        .ok_or(ContractError::NoPendingOwner {})?;
+    if pending_owner != info.sender {
+        return Err(ContractError::Unauthorized {});
     }
+
+    config.owner = pending_owner;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_accept_ownership")
+        .add_attribute("owner", info.sender))
 }
 
 pub fn execute_create_pot(
     deps: DepsMut,
     info: MessageInfo,
-    target_addr: String,
-    threshold: Uint128,
+    msg: CreatePotMsg,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-    if config.owner != info.sender {
+    let is_owner = config.owner == info.sender;
+    if !is_owner && !config.public_creation {
         return Err(ContractError::Unauthorized {});
     }
+    if msg.threshold.is_zero() {
+        return Err(ContractError::ZeroThreshold {});
+    }
+
+    let mut res = Response::new();
+
+    // owner creates pots for free, everyone else pays the configured fee
+    match &config.creation_fee {
+        Some(fee) if !is_owner => {
+            res = res
+                .add_message(creation_fee_msg(&config, &info, fee)?)
+                .add_attribute("creation_fee", fee_attribute(fee));
+        }
+        _ => nonpayable(&info)?,
+    }
+
+    // milestone pots pay each tranche to its own recipient
+    let (recipients, milestones) = match msg.milestones {
+        Some(milestones) => {
+            if msg.target_addr.is_some()
+                || msg.recipients.is_some()
+                || msg.recurring.unwrap_or(false)
+                || msg.approvers.is_some()
+            {
+                return Err(ContractError::InvalidMilestones {});
+            }
+            let milestones = validate_milestones(deps.as_ref(), milestones, msg.threshold)?;
+            (vec![], milestones)
+        }
+        None => (
+            validate_recipients(deps.as_ref(), msg.target_addr, msg.recipients)?,
+            vec![],
+        ),
+    };
+    if msg.receipt_min.is_some() && config.receipt_contract.is_none() {
+        return Err(ContractError::NoReceiptContract {});
+    }
+
+    let vesting = match msg.vesting_duration {
+        Some(duration) => {
+            if duration == 0 || msg.recurring.unwrap_or(false) || !milestones.is_empty() {
+                return Err(ContractError::InvalidVesting {});
+            }
+            Some(Vesting {
+                duration,
+                start: None,
+                claimed: Uint128::zero(),
+            })
+        }
+        None => None,
+    };
+
+    let approval_threshold = msg.approval_threshold;
+    let approval = msg
+        .approvers
+        .map(|approvers| validate_approval(deps.as_ref(), approvers, approval_threshold))
+        .transpose()?;
+    let recipients_attr = recipients
+        .iter()
+        .map(|r| format!("{}:{}", r.addr, r.weight))
+        .collect::<Vec<_>>()
+        .join(",");
 
     // create and save pot
     let pot = Pot {
-        target_addr: deps.api.addr_validate(target_addr.as_str())?,
-        threshold_amount: threshold,
+        creator: info.sender.clone(),
+        recipients,
+        threshold_amount: msg.threshold,
         collected: Uint128::zero(),
+        overshoot: msg.overshoot.unwrap_or(OvershootPolicy::Accept),
+        status: PotStatus::Open,
+        recurring: msg.recurring.unwrap_or(false),
+        cycle: 1,
+        milestones,
+        approval,
+        receipt_min: msg.receipt_min,
+        vesting,
     };
     save_pot(deps, &pot)?;
 
-    Ok(Response::new()
+    Ok(res
         .add_attribute("action", "execute_create_pot")
-        .add_attribute("target_addr", target_addr)
-        .add_attribute("threshold_amount", threshold))
+        .add_attribute("creator", info.sender)
+        .add_attribute("recipients", recipients_attr)
+        .add_attribute("milestones", pot.milestones.len().to_string())
+        .add_attribute("threshold_amount", msg.threshold))
+}
+
+/// Turns either a single target address or a weighted list into pot recipients
+fn validate_recipients(
+    deps: Deps,
+    target_addr: Option<String>,
+    recipients: Option<Vec<RecipientMsg>>,
+) -> Result<Vec<Recipient>, ContractError> {
+    match (target_addr, recipients) {
+        (Some(target_addr), None) => Ok(vec![Recipient {
+            addr: deps.api.addr_validate(&target_addr)?,
+            weight: TOTAL_WEIGHT,
+        }]),
+        (None, Some(recipients)) if !recipients.is_empty() => {
+            let total: u64 = recipients.iter().map(|r| r.weight as u64).sum();
+            if total != TOTAL_WEIGHT as u64 {
+                return Err(ContractError::InvalidWeights { total });
+            }
+            recipients
+                .into_iter()
+                .map(|r| {
+                    Ok(Recipient {
+                        addr: deps.api.addr_validate(&r.addr)?,
+                        weight: r.weight,
+                    })
+                })
+                .collect()
+        }
+        _ => Err(ContractError::InvalidRecipients {}),
+    }
+}
+
+/// Milestones need a positive amount each and must add up to the threshold
+fn validate_milestones(
+    deps: Deps,
+    milestones: Vec<MilestoneMsg>,
+    threshold: Uint128,
+) -> Result<Vec<Milestone>, ContractError> {
+    let mut total = Uint128::zero();
+    let milestones = milestones
+        .into_iter()
+        .map(|m| {
+            if m.amount.is_zero() {
+                return Err(ContractError::InvalidMilestones {});
+            }
+            total = total.checked_add(m.amount)?;
+            Ok(Milestone {
+                amount: m.amount,
+                recipient: deps.api.addr_validate(&m.recipient)?,
+                released: false,
+            })
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+    if milestones.is_empty() || total != threshold {
+        return Err(ContractError::InvalidMilestones {});
+    }
+    Ok(milestones)
+}
+
+/// Approvers must be unique, required approvals default to all of them
+fn validate_approval(
+    deps: Deps,
+    approvers: Vec<String>,
+    required: Option<u32>,
+) -> Result<Approval, ContractError> {
+    let mut validated: Vec<Addr> = vec![];
+    for approver in approvers {
+        let approver = deps.api.addr_validate(&approver)?;
+        if validated.contains(&approver) {
+            return Err(ContractError::InvalidApprovers {});
+        }
+        validated.push(approver);
+    }
+    let required = required.unwrap_or(validated.len() as u32);
+    if required == 0 || required as usize > validated.len() {
+        return Err(ContractError::InvalidApprovers {});
+    }
+    Ok(Approval {
+        approvers: validated,
+        required,
+        approved: vec![],
+        rejected: vec![],
+    })
+}
+
+/// Splits amount by recipient weights, rounding down each share.
+/// The rounding dust goes to the first recipient.
+pub fn split_payout(recipients: &[Recipient], amount: Uint128) -> Vec<(Addr, Uint128)> {
+    let mut shares: Vec<(Addr, Uint128)> = recipients
+        .iter()
+        .map(|r| {
+            (
+                r.addr.clone(),
+                amount.multiply_ratio(r.weight, TOTAL_WEIGHT),
+            )
+        })
+        .collect();
+    let distributed: Uint128 = shares.iter().map(|(_, share)| *share).sum();
+    if let Some((_, first)) = shares.first_mut() {
+        *first += amount - distributed;
+    }
+    shares
+}
+
+/// Builds the message forwarding the creation fee to the owner
+fn creation_fee_msg(
+    config: &Config,
+    info: &MessageInfo,
+    fee: &CreationFee,
+) -> Result<CosmosMsg, ContractError> {
+    match fee {
+        CreationFee::Native(coin) => {
+            if must_pay(info, &coin.denom)? != coin.amount {
+                return Err(ContractError::InvalidCreationFee { fee: coin.clone() });
+            }
+            Ok(BankMsg::Send {
+                to_address: config.owner.to_string(),
+                amount: vec![coin.clone()],
+            }
+            .into())
+        }
+        CreationFee::Cw20(amount) => {
+            nonpayable(info)?;
+            let cw20_addr = match &config.asset {
+                Asset::Cw20(addr) => addr.clone(),
+                Asset::Native(_) => return Err(ContractError::InvalidAsset {}),
+            };
+            Ok(Cw20Contract(cw20_addr).call(Cw20ExecuteMsg::TransferFrom {
+                owner: info.sender.to_string(),
+                recipient: config.owner.to_string(),
+                amount: *amount,
+            })?)
+        }
+    }
+}
+
+fn asset_attribute(asset: &Asset) -> String {
+    match asset {
+        Asset::Native(denom) => denom.clone(),
+        Asset::Cw20(addr) => addr.to_string(),
+    }
+}
+
+fn fee_attribute(fee: &CreationFee) -> String {
+    match fee {
+        CreationFee::Native(coin) => coin.to_string(),
+        CreationFee::Cw20(amount) => amount.to_string(),
+    }
 }
 
 pub fn execute_receive(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     wrapped: Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-    if config.cw20_addr != info.sender {
+    if config.asset != Asset::Cw20(info.sender) {
         return Err(ContractError::Unauthorized {});
     }
 
     let msg: ReceiveMsg = from_binary(&wrapped.msg)?;
     match msg {
-        ReceiveMsg::Send { id } => receive_send(deps, id, wrapped.amount, info.sender),
+        ReceiveMsg::Send { id } => {
+            receive_send(deps, env, id, wrapped.sender, wrapped.amount, &config.asset)
+        }
     }
 }
 
+pub fn execute_deposit(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pot_id: Uint64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let amount = match &config.asset {
+        Asset::Native(denom) => must_pay(&info, denom)?,
+        Asset::Cw20(_) => return Err(ContractError::WrongAsset {}),
+    };
+
+    let res = receive_send(
+        deps,
+        env,
+        pot_id,
+        info.sender.into_string(),
+        amount,
+        &config.asset,
+    )?;
+    Ok(res.add_attribute("method", "deposit"))
+}
+
+pub fn execute_deposit_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pot_id: Uint64,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let cw20 = match &config.asset {
+        Asset::Cw20(addr) => Cw20Contract(addr.clone()),
+        Asset::Native(_) => return Err(ContractError::WrongAsset {}),
+    };
+    let transfer_from = cw20.call(Cw20ExecuteMsg::TransferFrom {
+        owner: info.sender.to_string(),
+        recipient: env.contract.address.to_string(),
+        amount,
+    })?;
+
+    let mut res = receive_send(
+        deps,
+        env,
+        pot_id,
+        info.sender.into_string(),
+        amount,
+        &config.asset,
+    )?;
+    // tokens have to arrive before payouts or refunds spend them
+    res.messages.insert(0, SubMsg::new(transfer_from));
+
+    Ok(res.add_attribute("method", "deposit_from"))
+}
+
 pub fn receive_send(
     deps: DepsMut,
+    env: Env,
     pot_id: Uint64,
+    sender: String,
     amount: Uint128,
-    cw20_addr: Addr,
+    asset: &Asset,
 ) -> Result<Response, ContractError> {
-    let mut pot = POTS.load(deps.storage, pot_id.u64().into())?;
+    let mut pot = POTS.load(deps.storage, pot_id.u64())?;
+    if pot.status != PotStatus::Open {
+        return Err(ContractError::PotNotOpen {});
+    }
+
+    // split deposit into the part kept by the pot and the part returned to sender
+    let (accepted, excess) = match pot.overshoot {
+        OvershootPolicy::Accept => (amount, Uint128::zero()),
+        OvershootPolicy::Refund => {
+            let accepted = amount.min(pot.threshold_amount.checked_sub(pot.collected)?);
+            (accepted, amount.checked_sub(accepted)?)
+        }
+    };
+
+    pot.collected = pot.collected.checked_add(accepted)?;
 
-    pot.collected += amount;
+    // keep track of contributions for refunds
+    let contributor = deps.api.addr_validate(&sender)?;
+    CONTRIBUTIONS.update(
+        deps.storage,
+        (pot_id.u64(), &contributor),
+        |contribution| -> Result<_, ContractError> {
+            Ok(contribution.unwrap_or_default().checked_add(accepted)?)
+        },
+    )?;
 
-    POTS.save(deps.storage, pot_id.u64().into(), &pot)?;
+    let mut stats = STATS.load(deps.storage)?;
+    stats.total_collected = stats.total_collected.checked_add(accepted)?;
+    if !CONTRIBUTORS.has(deps.storage, &contributor) {
+        CONTRIBUTORS.save(deps.storage, &contributor, &Empty {})?;
+        stats.contributors += 1;
+    }
+    STATS.save(deps.storage, &stats)?;
 
     let mut res = Response::new()
         .add_attribute("action", "receive_send")
@@ -119,157 +605,788 @@ pub fn receive_send(
         .add_attribute("collected", pot.collected)
         .add_attribute("threshold", pot.threshold_amount);
 
-    // if collected exceeds threshold prepare a cw20 message
-    if pot.collected >= pot.threshold_amount {
-        let cw20 = Cw20Contract(cw20_addr);
-        // Build a cw20 transfer send msg, that send collected funds to target address
-        let msg = cw20.call(Cw20ExecuteMsg::Transfer {
-            recipient: pot.target_addr.into_string(),
-            amount: pot.collected,
-        })?;
-        res = res.add_message(msg);
+    // mint a receipt for large enough contributions on opted in pots
+    if let Some(receipt_min) = pot.receipt_min {
+        if !accepted.is_zero() && accepted >= receipt_min {
+            if let Some((token_id, msg)) =
+                receipt_msg(deps.storage, pot_id, &contributor, accepted)?
+            {
+                res = res.add_message(msg).add_attribute("receipt", token_id);
+            }
+        }
     }
 
-    Ok(res)
-}
-
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::GetConfig {} => to_binary(&query_config(deps)?),
-        QueryMsg::GetPot { id } => to_binary(&query_pot(deps, id)?),
+    if !pot.milestones.is_empty() {
+        // release every tranche whose milestone was crossed
+        let msgs = release_milestones(deps.storage, pot_id.u64(), &mut pot, asset)?;
+        res = res.add_attribute("milestones_released", msgs.len().to_string());
+        res = res.add_submessages(msgs);
+        if pot.milestones.iter().all(|m| m.released) {
+            PAYOUT_HEIGHTS.save(deps.storage, (pot_id.u64(), pot.cycle), &env.block.height)?;
+            pot.status = PotStatus::Paid;
+        }
+    } else if pot.collected >= pot.threshold_amount {
+        if pot.approval.is_some() {
+            // funds stay in the pot until approvers sign off
+            pot.status = PotStatus::AwaitingApproval;
+            res = res.add_attribute("status", "awaiting_approval");
+        } else {
+            res = res.add_attribute("cycle", pot.cycle.to_string());
+            let msgs = payout_pot(deps.storage, &env, pot_id.u64(), &mut pot, asset)?;
+            res = res.add_submessages(msgs);
+        }
     }
-}
 
-fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
-    let config = CONFIG.load(deps.storage)?;
-    Ok(ConfigResponse {
-        owner: config.owner,
-        cw20_addr: config.cw20_addr,
-    })
-}
+    update_pot(deps.storage, pot_id.u64(), &pot)?;
 
-fn query_pot(deps: Deps, id: Uint64) -> StdResult<PotResponse> {
-    let pot = POTS.load(deps.storage, id.u64().into())?;
-    Ok(PotResponse {
-        target_addr: pot.target_addr.into_string(),
-        collected: pot.collected,
-        threshold: pot.threshold_amount,
-    })
+    // return whatever went over the threshold
+    if !excess.is_zero() {
+        let msg = asset.transfer_msg(sender, excess)?;
+        res = res.add_message(msg).add_attribute("refund", excess);
+    }
+
+    Ok(res)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use cosmwasm_std::testing::{
-        mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info, MOCK_CONTRACT_ADDR,
+/// Builds the cw721 mint of a contribution receipt, none once the receipt contract was cleared
+fn receipt_msg(
+    storage: &mut dyn Storage,
+    pot_id: Uint64,
+    contributor: &Addr,
+    amount: Uint128,
+) -> Result<Option<(String, CosmosMsg)>, ContractError> {
+    let config = CONFIG.load(storage)?;
+    let receipt_contract = match config.receipt_contract {
+        Some(receipt_contract) => receipt_contract,
+        None => return Ok(None),
     };
-    use cosmwasm_std::{coins, from_binary, CosmosMsg, WasmMsg};
 
-    #[test]
-    fn proper_initialization_without_admin() {
-        let mut deps = mock_dependencies();
+    let seq = RECEIPT_SEQ.may_load(storage)?.unwrap_or_default() + 1;
+    RECEIPT_SEQ.save(storage, &seq)?;
+    let token_id = format!("{}-{}", pot_id, seq);
 
-        let msg = InstantiateMsg {
-            admin: None,
-            cw20_addr: "someone".to_string(),
-        };
-        let info = mock_info("creator", &coins(1000, "earth"));
+    let msg = WasmMsg::Execute {
+        contract_addr: receipt_contract.into_string(),
+        msg: to_binary(&ReceiptExecuteMsg::Mint(ReceiptMintMsg {
+            token_id: token_id.clone(),
+            owner: contributor.to_string(),
+            token_uri: None,
+            extension: ReceiptExtension { pot_id, amount },
+        }))?,
+        funds: vec![],
+    };
+    Ok(Some((token_id, msg.into())))
+}
 
-        // we can just call .unwrap() to assert this was a success
-        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
+/// Pays out a pot that reached its threshold, split between its recipients.
+/// Recurring pots pay out the threshold once for every cycle the collected funds
+/// complete and carry the rest to the next cycle.
+fn payout_pot(
+    storage: &mut dyn Storage,
+    env: &Env,
+    pot_id: u64,
+    pot: &mut Pot,
+    asset: &Asset,
+) -> Result<Vec<SubMsg>, ContractError> {
+    let (cycles, payout) = if pot.recurring {
+        let cycles = pot.collected.checked_div(pot.threshold_amount)?.u128() as u64;
+        let payout = pot.threshold_amount.checked_mul(cycles.into())?;
+        (cycles, payout)
+    } else {
+        (1, pot.collected)
+    };
+    let carry = pot.collected.checked_sub(payout)?;
 
-        // it worked, let's query the state
-        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
-        let value: ConfigResponse = from_binary(&res).unwrap();
-        assert_eq!("creator", value.owner.as_str());
-        assert_eq!("someone", value.cw20_addr.as_str());
+    for cycle in pot.cycle..pot.cycle + cycles {
+        PAYOUT_HEIGHTS.save(storage, (pot_id, cycle), &env.block.height)?;
     }
 
-    #[test]
-    fn proper_initialization_with_admin() {
-        let mut deps = mock_dependencies();
-
-        let msg = InstantiateMsg {
-            admin: Some("admin_addr".to_string()),
-            cw20_addr: "someone".to_string(),
-        };
-        let info = mock_info("creator", &coins(1000, "earth"));
+    // vesting pots keep the funds, recipients claim them as they vest
+    if let Some(vesting) = pot.vesting.as_mut() {
+        vesting.start = Some(env.block.time);
+        pot.status = PotStatus::Vesting;
+        return Ok(vec![]);
+    }
 
-        // we can just call .unwrap() to assert this was a success
-        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
+    // Build a transfer msg per recipient, that splits collected funds by weight
+    let mut msgs = vec![];
+    for (recipient, amount) in split_payout(&pot.recipients, payout) {
+        if amount.is_zero() {
+            continue;
+        }
+        msgs.push(payout_msg(storage, pot_id, asset, recipient, amount)?);
+    }
 
-        // it worked, let's query the state
-        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
-        let value: ConfigResponse = from_binary(&res).unwrap();
-        assert_eq!("admin_addr", value.owner.as_str());
-        assert_eq!("someone", value.cw20_addr.as_str());
+    if pot.recurring {
+        // contributions were paid out, only their share of the carry stays refundable
+        let contributions = load_contributions(storage, pot_id)?;
+        for (addr, amount) in refund_amounts(contributions, pot.collected, carry) {
+            if amount.is_zero() {
+                CONTRIBUTIONS.remove(storage, (pot_id, &addr));
+            } else {
+                CONTRIBUTIONS.save(storage, (pot_id, &addr), &amount)?;
+            }
+        }
+        if let Some(approval) = pot.approval.as_mut() {
+            approval.approved.clear();
+            approval.rejected.clear();
+        }
+        pot.collected = carry;
+        pot.cycle += cycles;
+        pot.status = PotStatus::Open;
+    } else {
+        pot.status = PotStatus::Paid;
     }
 
-    #[test]
-    fn create_pot() {
-        let mut deps = mock_dependencies();
+    Ok(msgs)
+}
 
-        let msg = InstantiateMsg {
-            admin: None,
-            cw20_addr: String::from(MOCK_CONTRACT_ADDR),
-        };
+/// Dispatches a payout transfer as a submessage, kept pending until its reply confirms it
+fn payout_msg(
+    storage: &mut dyn Storage,
+    pot_id: u64,
+    asset: &Asset,
+    recipient: Addr,
+    amount: Uint128,
+) -> Result<SubMsg, ContractError> {
+    let reply_id = PAYOUT_SEQ.may_load(storage)?.unwrap_or_default() + 1;
+    PAYOUT_SEQ.save(storage, &reply_id)?;
 
-        let info = mock_info("creator", &[]);
+    let msg = asset.transfer_msg(recipient.as_str(), amount)?;
+    PENDING_PAYOUTS.save(
+        storage,
+        reply_id,
+        &PendingPayout {
+            pot_id,
+            recipient,
+            amount,
+        },
+    )?;
 
-        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    Ok(SubMsg::reply_always(msg, reply_id))
+}
 
-        // should create pot
-        let msg = ExecuteMsg::CreatePot {
-            target_addr: String::from("Some"),
-            threshold: Uint128::new(100),
+/// Marks crossed milestones as released and builds their transfers.
+/// The last tranche also takes any overshoot accepted by the pot.
+fn release_milestones(
+    storage: &mut dyn Storage,
+    pot_id: u64,
+    pot: &mut Pot,
+    asset: &Asset,
+) -> Result<Vec<SubMsg>, ContractError> {
+    let mut msgs = vec![];
+    let mut reached = Uint128::zero();
+    let last = pot.milestones.len() - 1;
+    for (i, milestone) in pot.milestones.iter_mut().enumerate() {
+        let previous = reached;
+        reached = reached.checked_add(milestone.amount)?;
+        if milestone.released {
+            continue;
+        }
+        if pot.collected < reached {
+            break;
+        }
+        let amount = if i == last {
+            pot.collected.checked_sub(previous)?
+        } else {
+            milestone.amount
         };
+        msgs.push(payout_msg(
+            storage,
+            pot_id,
+            asset,
+            milestone.recipient.clone(),
+            amount,
+        )?);
+        milestone.released = true;
+    }
+    Ok(msgs)
+}
 
-        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(res.messages.len(), 0);
+/// Scales contributions down to what is left in the pot, rounding down.
+/// The rounding dust goes to the first contributor.
+fn refund_amounts(
+    contributions: Vec<(Addr, Uint128)>,
+    collected: Uint128,
+    remaining: Uint128,
+) -> Vec<(Addr, Uint128)> {
+    if remaining == collected {
+        return contributions;
+    }
+    let mut refunds: Vec<(Addr, Uint128)> = contributions
+        .into_iter()
+        .map(|(addr, amount)| (addr, amount.multiply_ratio(remaining, collected)))
+        .collect();
+    let refunded: Uint128 = refunds.iter().map(|(_, amount)| *amount).sum();
+    if let Some((_, first)) = refunds.first_mut() {
+        *first += remaining - refunded;
+    }
+    refunds
+}
 
-        // query pot
-        let msg = QueryMsg::GetPot { id: Uint64::new(1) };
-        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+fn load_contributions(storage: &dyn Storage, pot_id: u64) -> StdResult<Vec<(Addr, Uint128)>> {
+    CONTRIBUTIONS
+        .prefix(pot_id)
+        .range(storage, None, None, Order::Ascending)
+        .collect()
+}
 
-        let pot: PotResponse = from_binary(&res).unwrap();
-        assert_eq!(
-            pot,
-            PotResponse {
-                target_addr: Addr::unchecked("Some").to_string(),
-                threshold: Uint128::new(100),
-                collected: Default::default(),
-            }
-        );
+/// Returns the remaining funds of a pot to its contributors
+fn refund_msgs(
+    storage: &dyn Storage,
+    pot_id: u64,
+    pot: &Pot,
+    remaining: Uint128,
+    asset: &Asset,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let contributions = load_contributions(storage, pot_id)?;
+    let mut msgs = vec![];
+    for (contributor, amount) in refund_amounts(contributions, pot.collected, remaining) {
+        if amount.is_zero() {
+            continue;
+        }
+        msgs.push(asset.transfer_msg(contributor, amount)?);
     }
+    Ok(msgs)
+}
 
-    #[test]
-    fn test_receive_send() {
-        let mut deps = mock_dependencies();
+pub fn execute_cancel_pot(
+    deps: DepsMut,
+    info: MessageInfo,
+    pot_id: Uint64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut pot = POTS.load(deps.storage, pot_id.u64())?;
+    if config.owner != info.sender && pot.creator != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !pot.is_active() {
+        return Err(ContractError::PotNotOpen {});
+    }
+    // deposits from before the migration can only go to the target
+    if MIGRATED_POTS.has(deps.storage, pot_id.u64()) {
+        return Err(ContractError::MigratedPot {});
+    }
 
-        let msg = InstantiateMsg {
-            admin: None,
-            cw20_addr: String::from("cw20"),
-        };
-        let mut info = mock_info("creator", &[]);
+    pot.status = PotStatus::Cancelled;
+    update_pot(deps.storage, pot_id.u64(), &pot)?;
 
-        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    // released milestone tranches are not returned
+    let remaining = pot.collected.checked_sub(pot.released())?;
 
-        // should create pot
-        let msg = ExecuteMsg::CreatePot {
-            target_addr: String::from("Some"),
-            threshold: Uint128::new(100),
-        };
+    let mut res = Response::new()
+        .add_attribute("action", "execute_cancel_pot")
+        .add_attribute("pot_id", pot_id)
+        .add_attribute("collected", pot.collected)
+        .add_attribute("remaining", remaining);
 
-        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
-        assert_eq!(res.messages.len(), 0);
+    match config.fallback_addr {
+        Some(fallback_addr) => {
+            if !remaining.is_zero() {
+                res = res.add_message(config.asset.transfer_msg(&fallback_addr, remaining)?);
+            }
+            res = res.add_attribute("fallback_addr", fallback_addr);
+        }
+        None => {
+            let msgs = refund_msgs(deps.storage, pot_id.u64(), &pot, remaining, &config.asset)?;
+            res = res.add_messages(msgs);
+            res = res.add_attribute("refunded", "contributors");
+        }
+    }
 
-        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-            sender: String::from("cw20"),
-            amount: Uint128::new(55),
-            msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(1) }).unwrap(),
+    Ok(res)
+}
+
+pub fn execute_approve_payout(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pot_id: Uint64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut pot = POTS.load(deps.storage, pot_id.u64())?;
+    let approval = pot
+        .approval
+        .as_mut()
+        .ok_or(ContractError::Unauthorized {})?;
+    if !approval.approvers.contains(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if pot.status != PotStatus::AwaitingApproval {
+        return Err(ContractError::NotAwaitingApproval {});
+    }
+    if approval.has_voted(&info.sender) {
+        return Err(ContractError::AlreadyVoted {});
+    }
+    approval.approved.push(info.sender.clone());
+    let approved = approval.is_approved();
+
+    let mut res = Response::new()
+        .add_attribute("action", "execute_approve_payout")
+        .add_attribute("pot_id", pot_id)
+        .add_attribute("approver", info.sender);
+
+    // release funds once enough approvers signed
+    if approved {
+        res = res.add_attribute("cycle", pot.cycle.to_string());
+        let msgs = payout_pot(deps.storage, &env, pot_id.u64(), &mut pot, &config.asset)?;
+        res = res.add_submessages(msgs);
+    }
+
+    update_pot(deps.storage, pot_id.u64(), &pot)?;
+
+    Ok(res)
+}
+
+pub fn execute_reject_payout(
+    deps: DepsMut,
+    info: MessageInfo,
+    pot_id: Uint64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut pot = POTS.load(deps.storage, pot_id.u64())?;
+    let approval = pot
+        .approval
+        .as_mut()
+        .ok_or(ContractError::Unauthorized {})?;
+    if !approval.approvers.contains(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if pot.status != PotStatus::AwaitingApproval {
+        return Err(ContractError::NotAwaitingApproval {});
+    }
+    if approval.has_voted(&info.sender) {
+        return Err(ContractError::AlreadyVoted {});
+    }
+    approval.rejected.push(info.sender.clone());
+    let rejected = approval.is_rejected();
+
+    let mut res = Response::new()
+        .add_attribute("action", "execute_reject_payout")
+        .add_attribute("pot_id", pot_id)
+        .add_attribute("approver", info.sender);
+
+    // refund contributors once the payout can no longer be approved
+    if rejected {
+        pot.status = PotStatus::Rejected;
+        let msgs = refund_msgs(
+            deps.storage,
+            pot_id.u64(),
+            &pot,
+            pot.collected,
+            &config.asset,
+        )?;
+        res = res
+            .add_messages(msgs)
+            .add_attribute("refunded", "contributors");
+    }
+
+    update_pot(deps.storage, pot_id.u64(), &pot)?;
+
+    Ok(res)
+}
+
+pub fn execute_claim_vested(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pot_id: Uint64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut pot = POTS.load(deps.storage, pot_id.u64())?;
+    if !pot.recipients.iter().any(|r| r.addr == info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if pot.status != PotStatus::Vesting {
+        return Err(ContractError::NotVesting {});
+    }
+    let vesting = pot.vesting.as_mut().ok_or(ContractError::NotVesting {})?;
+
+    let claimable = vesting
+        .vested(pot.collected, env.block.time)
+        .checked_sub(vesting.claimed)?;
+    if claimable.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    vesting.claimed = vesting.claimed.checked_add(claimable)?;
+    if vesting.claimed == pot.collected {
+        pot.status = PotStatus::Paid;
+    }
+
+    // every recipient gets its share of the claim
+    let mut msgs = vec![];
+    for (recipient, amount) in split_payout(&pot.recipients, claimable) {
+        if amount.is_zero() {
+            continue;
+        }
+        msgs.push(payout_msg(
+            deps.storage,
+            pot_id.u64(),
+            &config.asset,
+            recipient,
+            amount,
+        )?);
+    }
+
+    update_pot(deps.storage, pot_id.u64(), &pot)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_claim_vested")
+        .add_attribute("pot_id", pot_id)
+        .add_attribute("claimed", claimable)
+        .add_submessages(msgs))
+}
+
+pub fn execute_retry_payout(
+    deps: DepsMut,
+    info: MessageInfo,
+    pot_id: Uint64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut pot = POTS.load(deps.storage, pot_id.u64())?;
+    if pot.status != PotStatus::PayoutFailed {
+        return Err(ContractError::NoFailedPayout {});
+    }
+
+    let mut failed = vec![];
+    for item in PENDING_PAYOUTS.range(deps.storage, None, None, Order::Ascending) {
+        let (reply_id, payout) = item?;
+        if payout.pot_id == pot_id.u64() {
+            failed.push((reply_id, payout));
+        }
+    }
+
+    // dispatch failed transfers again under new reply ids
+    let mut msgs = vec![];
+    for (reply_id, payout) in failed {
+        PENDING_PAYOUTS.remove(deps.storage, reply_id);
+        msgs.push(payout_msg(
+            deps.storage,
+            payout.pot_id,
+            &config.asset,
+            payout.recipient,
+            payout.amount,
+        )?);
+    }
+
+    pot.status = pot.settled_status();
+    update_pot(deps.storage, pot_id.u64(), &pot)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_retry_payout")
+        .add_attribute("pot_id", pot_id)
+        .add_attribute("transfers", msgs.len().to_string())
+        .add_submessages(msgs))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let payout = PENDING_PAYOUTS.load(deps.storage, msg.id)?;
+    let res = Response::new()
+        .add_attribute("action", "reply_payout")
+        .add_attribute("pot_id", payout.pot_id.to_string())
+        .add_attribute("recipient", payout.recipient.as_str())
+        .add_attribute("amount", payout.amount);
+
+    match msg.result {
+        SubMsgResult::Ok(_) => {
+            PENDING_PAYOUTS.remove(deps.storage, msg.id);
+            STATS.update(deps.storage, |mut stats| -> StdResult<_> {
+                stats.total_paid_out = stats.total_paid_out.checked_add(payout.amount)?;
+                Ok(stats)
+            })?;
+            Ok(res.add_attribute("result", "success"))
+        }
+        SubMsgResult::Err(err) => {
+            // keep the transfer pending so the owner can retry it
+            let mut pot = POTS.load(deps.storage, payout.pot_id)?;
+            pot.status = PotStatus::PayoutFailed;
+            update_pot(deps.storage, payout.pot_id, &pot)?;
+            Ok(res
+                .add_attribute("result", "failed")
+                .add_attribute("error", err))
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::WrongContract {
+            contract: stored.contract,
+        });
+    }
+    let version: semver::Version = stored.version.parse()?;
+    let current: semver::Version = CONTRACT_VERSION.parse()?;
+    if version > current {
+        return Err(ContractError::CannotDowngrade {
+            version: stored.version,
+        });
+    }
+
+    if version < semver::Version::new(0, 2, 0) {
+        let old_config = v0_1::CONFIG.load(deps.storage)?;
+        let config = Config {
+            owner: old_config.owner,
+            asset: Asset::Cw20(old_config.cw20_addr),
+            public_creation: msg.public_creation.unwrap_or(false),
+            creation_fee: msg.creation_fee,
+            pending_owner: None,
+            fallback_addr: msg
+                .fallback_addr
+                .map(|s| deps.api.addr_validate(s.as_str()))
+                .transpose()?,
+            receipt_contract: msg
+                .receipt_contract
+                .map(|s| deps.api.addr_validate(s.as_str()))
+                .transpose()?,
+        };
+        CONFIG.save(deps.storage, &config)?;
+        migrate_pots_from_v0_1(deps.storage, &config.owner)?;
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("previous_version", stored.version)
+        .add_attribute("version", CONTRACT_VERSION))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::GetConfig {} => to_binary(&query_config(deps)?),
+        QueryMsg::GetPot { id } => to_binary(&query_pot(deps, id)?),
+        QueryMsg::PotCycles { id } => to_binary(&query_pot_cycles(deps, id)?),
+        QueryMsg::Stats {} => to_binary(&query_stats(deps)?),
+        QueryMsg::VestingStatus { id } => to_binary(&query_vesting_status(deps, env, id)?),
+    }
+}
+
+fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        owner: config.owner,
+        asset: config.asset,
+        public_creation: config.public_creation,
+        creation_fee: config.creation_fee,
+        pending_owner: config.pending_owner,
+        fallback_addr: config.fallback_addr,
+        receipt_contract: config.receipt_contract,
+    })
+}
+
+fn query_pot(deps: Deps, id: Uint64) -> StdResult<PotResponse> {
+    let pot = POTS.load(deps.storage, id.u64())?;
+    Ok(PotResponse {
+        creator: pot.creator.into_string(),
+        recipients: pot.recipients,
+        collected: pot.collected,
+        threshold: pot.threshold_amount,
+        overshoot: pot.overshoot,
+        status: pot.status,
+        recurring: pot.recurring,
+        cycle: pot.cycle,
+        milestones: pot.milestones,
+        approval: pot.approval,
+        receipt_min: pot.receipt_min,
+        vesting: pot.vesting,
+    })
+}
+
+fn query_stats(deps: Deps) -> StdResult<StatsResponse> {
+    STATS.load(deps.storage)
+}
+
+fn query_pot_cycles(deps: Deps, id: Uint64) -> StdResult<PotCyclesResponse> {
+    let pot = POTS.load(deps.storage, id.u64())?;
+    let payout_heights = PAYOUT_HEIGHTS
+        .prefix(id.u64())
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, height)| height))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(PotCyclesResponse {
+        cycle: pot.cycle,
+        payout_heights,
+    })
+}
+
+fn query_vesting_status(deps: Deps, env: Env, id: Uint64) -> StdResult<VestingStatusResponse> {
+    let pot = POTS.load(deps.storage, id.u64())?;
+    let vesting = pot
+        .vesting
+        .ok_or_else(|| StdError::generic_err("Pot is not vesting"))?;
+    let vested = vesting.vested(pot.collected, env.block.time);
+    Ok(VestingStatusResponse {
+        duration: vesting.duration,
+        start: vesting.start,
+        end: vesting.end(),
+        total: pot.collected,
+        vested,
+        claimed: vesting.claimed,
+        claimable: vested.checked_sub(vesting.claimed)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::StatusCounts;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::{
+        attr, coin, coins, from_binary, CosmosMsg, ReplyOn, SubMsgExecutionResponse, WasmMsg,
+    };
+
+    fn create_pot_msg(target_addr: &str, threshold: u128) -> ExecuteMsg {
+        ExecuteMsg::CreatePot(CreatePotMsg {
+            target_addr: Some(String::from(target_addr)),
+            recipients: None,
+            threshold: Uint128::new(threshold),
+            overshoot: None,
+            recurring: None,
+            milestones: None,
+            approvers: None,
+            approval_threshold: None,
+            receipt_min: None,
+            vesting_duration: None,
+        })
+    }
+
+    #[test]
+    fn proper_initialization_without_admin() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some("someone".to_string()),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+
+        // we can just call .unwrap() to assert this was a success
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // it worked, let's query the state
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let value: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!("creator", value.owner.as_str());
+        assert_eq!(Asset::Cw20(Addr::unchecked("someone")), value.asset);
+    }
+
+    #[test]
+    fn proper_initialization_with_admin() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Some("admin_addr".to_string()),
+            cw20_addr: Some("someone".to_string()),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+
+        // we can just call .unwrap() to assert this was a success
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // it worked, let's query the state
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let value: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!("admin_addr", value.owner.as_str());
+        assert_eq!(Asset::Cw20(Addr::unchecked("someone")), value.asset);
+    }
+
+    #[test]
+    fn create_pot() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from(MOCK_CONTRACT_ADDR)),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+
+        let info = mock_info("creator", &[]);
+
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // should create pot
+        let msg = create_pot_msg("Some", 100);
+
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.messages.len(), 0);
+
+        // query pot
+        let msg = QueryMsg::GetPot { id: Uint64::new(1) };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+
+        let pot: PotResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            pot,
+            PotResponse {
+                creator: String::from("creator"),
+                recipients: vec![Recipient {
+                    addr: Addr::unchecked("Some"),
+                    weight: TOTAL_WEIGHT,
+                }],
+                threshold: Uint128::new(100),
+                collected: Default::default(),
+                overshoot: OvershootPolicy::Accept,
+                status: PotStatus::Open,
+                recurring: false,
+                cycle: 1,
+                milestones: vec![],
+                approval: None,
+                receipt_min: None,
+                vesting: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_receive_send() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let mut info = mock_info("creator", &[]);
+
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // should create pot
+        let msg = create_pot_msg("Some", 100);
+
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        assert_eq!(res.messages.len(), 0);
+
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("cw20"),
+            amount: Uint128::new(55),
+            msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(1) }).unwrap(),
         });
         info.sender = Addr::unchecked("cw20");
         let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
@@ -282,9 +1399,21 @@ mod tests {
         assert_eq!(
             pot,
             PotResponse {
-                target_addr: Addr::unchecked("Some").to_string(),
+                creator: String::from("creator"),
+                recipients: vec![Recipient {
+                    addr: Addr::unchecked("Some"),
+                    weight: TOTAL_WEIGHT,
+                }],
                 threshold: Uint128::new(100),
                 collected: Uint128::new(55),
+                overshoot: OvershootPolicy::Accept,
+                status: PotStatus::Open,
+                recurring: false,
+                cycle: 1,
+                milestones: vec![],
+                approval: None,
+                receipt_min: None,
+                vesting: None,
             }
         );
 
@@ -317,10 +1446,2063 @@ mod tests {
         assert_eq!(
             pot,
             PotResponse {
-                target_addr: Addr::unchecked("Some").to_string(),
+                creator: String::from("creator"),
+                recipients: vec![Recipient {
+                    addr: Addr::unchecked("Some"),
+                    weight: TOTAL_WEIGHT,
+                }],
                 threshold: Uint128::new(100),
                 collected: Uint128::new(110),
+                overshoot: OvershootPolicy::Accept,
+                status: PotStatus::Paid,
+                recurring: false,
+                cycle: 1,
+                milestones: vec![],
+                approval: None,
+                receipt_min: None,
+                vesting: None,
             }
         );
     }
+
+    #[test]
+    fn create_pot_public() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = create_pot_msg("Some", 100);
+
+        // anyone cannot create pot by default
+        let info = mock_info("anyone", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // with public creation and no fee anyone can create a pot
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: Some(true),
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = create_pot_msg("Some", 100);
+        let info = mock_info("anyone", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.messages.len(), 0);
+
+        let msg = QueryMsg::GetPot { id: Uint64::new(1) };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let pot: PotResponse = from_binary(&res).unwrap();
+        assert_eq!(pot.creator, "anyone");
+    }
+
+    #[test]
+    fn create_pot_native_fee() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: Some(true),
+            creation_fee: Some(CreationFee::Native(coin(10, "earth"))),
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = create_pot_msg("Some", 100);
+
+        // no fee cannot create
+        let info = mock_info("anyone", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        match err {
+            ContractError::Payment(_) => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // wrong fee cannot create
+        let info = mock_info("anyone", &coins(9, "earth"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        match err {
+            ContractError::InvalidCreationFee { fee } => assert_eq!(fee, coin(10, "earth")),
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // proper fee is forwarded to the owner
+        let info = mock_info("anyone", &coins(10, "earth"));
+        let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("creator"),
+                amount: coins(10, "earth"),
+            })
+        );
+
+        // owner does not pay the fee
+        let info = mock_info("creator", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.messages.len(), 0);
+    }
+
+    #[test]
+    fn create_pot_cw20_fee() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: Some(true),
+            creation_fee: Some(CreationFee::Cw20(Uint128::new(10))),
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // native funds are not taken as a cw20 fee
+        let msg = create_pot_msg("Some", 100);
+        let info = mock_info("anyone", &coins(10, "ujuno"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        match err {
+            ContractError::Payment(_) => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // nor by the owner who pays no fee
+        let info = mock_info("creator", &coins(10, "ujuno"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        match err {
+            ContractError::Payment(_) => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let info = mock_info("anyone", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("cw20"),
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: String::from("anyone"),
+                    recipient: String::from("creator"),
+                    amount: Uint128::new(10)
+                })
+                .unwrap(),
+                funds: vec![]
+            })
+        );
+    }
+
+    #[test]
+    fn create_pot_recipients() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // weights must sum up to 10000
+        let msg = ExecuteMsg::CreatePot(CreatePotMsg {
+            target_addr: None,
+            recipients: Some(vec![
+                RecipientMsg {
+                    addr: String::from("alice"),
+                    weight: 5000,
+                },
+                RecipientMsg {
+                    addr: String::from("bob"),
+                    weight: 4000,
+                },
+            ]),
+            threshold: Uint128::new(100),
+            overshoot: None,
+            recurring: None,
+            milestones: None,
+            approvers: None,
+            approval_threshold: None,
+            receipt_min: None,
+            vesting_duration: None,
+        });
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        match err {
+            ContractError::InvalidWeights { total } => assert_eq!(total, 9000),
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // both target_addr and recipients cannot be set
+        let msg = ExecuteMsg::CreatePot(CreatePotMsg {
+            target_addr: Some(String::from("Some")),
+            recipients: Some(vec![RecipientMsg {
+                addr: String::from("alice"),
+                weight: 10000,
+            }]),
+            threshold: Uint128::new(100),
+            overshoot: None,
+            recurring: None,
+            milestones: None,
+            approvers: None,
+            approval_threshold: None,
+            receipt_min: None,
+            vesting_duration: None,
+        });
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        match err {
+            ContractError::InvalidRecipients {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // one of them must be set
+        let msg = ExecuteMsg::CreatePot(CreatePotMsg {
+            target_addr: None,
+            recipients: Some(vec![]),
+            threshold: Uint128::new(100),
+            overshoot: None,
+            recurring: None,
+            milestones: None,
+            approvers: None,
+            approval_threshold: None,
+            receipt_min: None,
+            vesting_duration: None,
+        });
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        match err {
+            ContractError::InvalidRecipients {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn receive_send_split_payout() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let mut info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePot(CreatePotMsg {
+            target_addr: None,
+            recipients: Some(vec![
+                RecipientMsg {
+                    addr: String::from("alice"),
+                    weight: 3333,
+                },
+                RecipientMsg {
+                    addr: String::from("bob"),
+                    weight: 3333,
+                },
+                RecipientMsg {
+                    addr: String::from("carol"),
+                    weight: 3334,
+                },
+            ]),
+            threshold: Uint128::new(100),
+            overshoot: None,
+            recurring: None,
+            milestones: None,
+            approvers: None,
+            approval_threshold: None,
+            receipt_min: None,
+            vesting_duration: None,
+        });
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("cw20"),
+            amount: Uint128::new(101),
+            msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(1) }).unwrap(),
+        });
+        info.sender = Addr::unchecked("cw20");
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // 33 + 33 + 33 leaves 2 dust, which goes to the first recipient
+        let expected = vec![("alice", 35u128), ("bob", 33), ("carol", 33)];
+        assert_eq!(res.messages.len(), expected.len());
+        for (msg, (recipient, amount)) in res.messages.iter().zip(expected) {
+            assert_eq!(
+                msg.msg,
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: String::from("cw20"),
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: String::from(recipient),
+                        amount: Uint128::new(amount)
+                    })
+                    .unwrap(),
+                    funds: vec![]
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn receive_send_refund_excess() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let mut info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePot(CreatePotMsg {
+            target_addr: Some(String::from("Some")),
+            recipients: None,
+            threshold: Uint128::new(100),
+            overshoot: Some(OvershootPolicy::Refund),
+            recurring: None,
+            milestones: None,
+            approvers: None,
+            approval_threshold: None,
+            receipt_min: None,
+            vesting_duration: None,
+        });
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let send = |amount: u128| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: String::from("donor"),
+                amount: Uint128::new(amount),
+                msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(1) }).unwrap(),
+            })
+        };
+        info.sender = Addr::unchecked("cw20");
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), send(60)).unwrap();
+
+        // pot is capped at the threshold and the excess goes back to the donor
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), send(60)).unwrap();
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("cw20"),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: String::from("Some"),
+                    amount: Uint128::new(100)
+                })
+                .unwrap(),
+                funds: vec![]
+            })
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("cw20"),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: String::from("donor"),
+                    amount: Uint128::new(20)
+                })
+                .unwrap(),
+                funds: vec![]
+            })
+        );
+
+        let msg = QueryMsg::GetPot { id: Uint64::new(1) };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let pot: PotResponse = from_binary(&res).unwrap();
+        assert_eq!(pot.collected, Uint128::new(100));
+
+        // full pot rejects further deposits
+        let err = execute(deps.as_mut(), mock_env(), info, send(1)).unwrap_err();
+        match err {
+            ContractError::PotNotOpen {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn receive_send_overflow() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let mut info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = create_pot_msg("Some", u128::MAX);
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let send = |amount: u128| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: String::from("donor"),
+                amount: Uint128::new(amount),
+                msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(1) }).unwrap(),
+            })
+        };
+        info.sender = Addr::unchecked("cw20");
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), send(u128::MAX - 1)).unwrap();
+
+        let err = execute(deps.as_mut(), mock_env(), info, send(2)).unwrap_err();
+        match err {
+            ContractError::Overflow(_) => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn initialization_with_invalid_admin() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: Some("a".to_string()),
+            cw20_addr: Some("someone".to_string()),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &[]);
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        match err {
+            ContractError::Std(_) => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn update_config() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+            cw20_addr: Some(String::from("new_cw20")),
+            native_denom: None,
+            public_creation: Some(true),
+            fallback_addr: None,
+            clear_fallback_addr: None,
+            receipt_contract: None,
+            clear_receipt_contract: None,
+        });
+
+        // random cannot update config
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // cw20 cannot change while a pot holds a balance
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            create_pot_msg("Some", 100),
+        )
+        .unwrap();
+        let deposit = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("donor"),
+            amount: Uint128::new(50),
+            msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(1) }).unwrap(),
+        });
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("cw20", &[]), deposit).unwrap();
+
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap_err();
+        match err {
+            ContractError::PotsHoldBalance {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // once the pot is paid out cw20 can change
+        let deposit = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("donor"),
+            amount: Uint128::new(50),
+            msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(1) }).unwrap(),
+        });
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("cw20", &[]), deposit).unwrap();
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let value: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(Asset::Cw20(Addr::unchecked("new_cw20")), value.asset);
+        assert!(value.public_creation);
+    }
+
+    #[test]
+    fn transfer_ownership() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // nothing to accept yet
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("new_owner", &[]),
+            ExecuteMsg::AcceptOwnership {},
+        )
+        .unwrap_err();
+        match err {
+            ContractError::NoPendingOwner {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let msg = ExecuteMsg::TransferOwnership {
+            new_owner: String::from("new_owner"),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // only pending owner can accept
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::AcceptOwnership {},
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("new_owner", &[]),
+            ExecuteMsg::AcceptOwnership {},
+        )
+        .unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let value: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!("new_owner", value.owner.as_str());
+        assert_eq!(None, value.pending_owner);
+    }
+
+    #[test]
+    fn cancel_pot_refunds_contributors() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: Some(true),
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let pot_creator = mock_info("pot_creator", &[]);
+        let msg = create_pot_msg("Some", 100);
+        let _res = execute(deps.as_mut(), mock_env(), pot_creator.clone(), msg).unwrap();
+
+        for (donor, amount) in [("alice", 10u128), ("bob", 20), ("alice", 5)] {
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: String::from(donor),
+                amount: Uint128::new(amount),
+                msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(1) }).unwrap(),
+            });
+            let _res = execute(deps.as_mut(), mock_env(), mock_info("cw20", &[]), msg).unwrap();
+        }
+
+        // random cannot cancel
+        let msg = ExecuteMsg::CancelPot { id: Uint64::new(1) };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // pot creator can cancel, contributors are refunded
+        let res = execute(deps.as_mut(), mock_env(), pot_creator, msg.clone()).unwrap();
+        let expected = vec![("alice", 15u128), ("bob", 20)];
+        assert_eq!(res.messages.len(), expected.len());
+        for (msg, (recipient, amount)) in res.messages.iter().zip(expected) {
+            assert_eq!(
+                msg.msg,
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: String::from("cw20"),
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: String::from(recipient),
+                        amount: Uint128::new(amount)
+                    })
+                    .unwrap(),
+                    funds: vec![]
+                })
+            );
+        }
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetPot { id: Uint64::new(1) },
+        )
+        .unwrap();
+        let pot: PotResponse = from_binary(&res).unwrap();
+        assert_eq!(pot.status, PotStatus::Cancelled);
+
+        // cancelled pot cannot be cancelled again or receive deposits
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        match err {
+            ContractError::PotNotOpen {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("alice"),
+            amount: Uint128::new(1),
+            msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(1) }).unwrap(),
+        });
+        let err = execute(deps.as_mut(), mock_env(), mock_info("cw20", &[]), msg).unwrap_err();
+        match err {
+            ContractError::PotNotOpen {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn cancel_pot_fallback() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: Some(String::from("fallback")),
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = create_pot_msg("Some", 100);
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("alice"),
+            amount: Uint128::new(40),
+            msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(1) }).unwrap(),
+        });
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("cw20", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::CancelPot { id: Uint64::new(1) };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("cw20"),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: String::from("fallback"),
+                    amount: Uint128::new(40)
+                })
+                .unwrap(),
+                funds: vec![]
+            })
+        );
+
+        // the fallback cannot be set and cleared at once
+        let update = |fallback_addr: Option<&str>| {
+            ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+                cw20_addr: None,
+                native_denom: None,
+                public_creation: None,
+                fallback_addr: fallback_addr.map(String::from),
+                clear_fallback_addr: Some(true),
+                receipt_contract: None,
+                clear_receipt_contract: None,
+            })
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            update(Some("other")),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::ConflictingConfig { field } => assert_eq!(field, "fallback_addr"),
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // once cleared, cancelled pots refund contributors again
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), update(None)).unwrap();
+        assert_eq!(res.attributes[1], attr("fallback_addr", "none"));
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let config: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(config.fallback_addr, None);
+
+        let msg = create_pot_msg("Some", 100);
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("alice"),
+            amount: Uint128::new(25),
+            msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(2) }).unwrap(),
+        });
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("cw20", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::CancelPot { id: Uint64::new(2) };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("cw20"),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: String::from("alice"),
+                    amount: Uint128::new(25)
+                })
+                .unwrap(),
+                funds: vec![]
+            })
+        );
+    }
+
+    #[test]
+    fn zero_threshold() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let err = execute(deps.as_mut(), mock_env(), info, create_pot_msg("Some", 0)).unwrap_err();
+        match err {
+            ContractError::ZeroThreshold {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn recurring_pot() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePot(CreatePotMsg {
+            target_addr: Some(String::from("Some")),
+            recipients: None,
+            threshold: Uint128::new(100),
+            overshoot: None,
+            recurring: Some(true),
+            milestones: None,
+            approvers: None,
+            approval_threshold: None,
+            receipt_min: None,
+            vesting_duration: None,
+        });
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let send = |amount: u128| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: String::from("donor"),
+                amount: Uint128::new(amount),
+                msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(1) }).unwrap(),
+            })
+        };
+
+        // first cycle pays out the threshold and carries the overshoot
+        let mut env = mock_env();
+        env.block.height = 100;
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("cw20", &[]),
+            send(130),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("cw20"),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: String::from("Some"),
+                    amount: Uint128::new(100)
+                })
+                .unwrap(),
+                funds: vec![]
+            })
+        );
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetPot { id: Uint64::new(1) },
+        )
+        .unwrap();
+        let pot: PotResponse = from_binary(&res).unwrap();
+        assert_eq!(pot.status, PotStatus::Open);
+        assert_eq!(pot.collected, Uint128::new(30));
+        assert_eq!(pot.cycle, 2);
+
+        // second cycle
+        env.block.height = 200;
+        let res = execute(deps.as_mut(), env.clone(), mock_info("cw20", &[]), send(70)).unwrap();
+        assert_eq!(res.messages.len(), 1);
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::PotCycles { id: Uint64::new(1) },
+        )
+        .unwrap();
+        let cycles: PotCyclesResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            cycles,
+            PotCyclesResponse {
+                cycle: 3,
+                payout_heights: vec![100, 200],
+            }
+        );
+
+        // a deposit completing several cycles pays all of them at once
+        env.block.height = 300;
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("cw20", &[]),
+            send(250),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("cw20"),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: String::from("Some"),
+                    amount: Uint128::new(200)
+                })
+                .unwrap(),
+                funds: vec![]
+            })
+        );
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetPot { id: Uint64::new(1) },
+        )
+        .unwrap();
+        let pot: PotResponse = from_binary(&res).unwrap();
+        assert_eq!(pot.status, PotStatus::Open);
+        assert_eq!(pot.collected, Uint128::new(50));
+        assert_eq!(pot.cycle, 5);
+
+        let res = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::PotCycles { id: Uint64::new(1) },
+        )
+        .unwrap();
+        let cycles: PotCyclesResponse = from_binary(&res).unwrap();
+        assert_eq!(cycles.payout_heights, vec![100, 200, 300, 300]);
+    }
+
+    #[test]
+    fn milestone_pot() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let milestones = vec![
+            MilestoneMsg {
+                amount: Uint128::new(30),
+                recipient: String::from("design"),
+            },
+            MilestoneMsg {
+                amount: Uint128::new(50),
+                recipient: String::from("build"),
+            },
+            MilestoneMsg {
+                amount: Uint128::new(20),
+                recipient: String::from("launch"),
+            },
+        ];
+
+        // milestones must add up to the threshold
+        let msg = ExecuteMsg::CreatePot(CreatePotMsg {
+            target_addr: None,
+            recipients: None,
+            threshold: Uint128::new(90),
+            overshoot: None,
+            recurring: None,
+            milestones: Some(milestones.clone()),
+            approvers: None,
+            approval_threshold: None,
+            receipt_min: None,
+            vesting_duration: None,
+        });
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        match err {
+            ContractError::InvalidMilestones {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let msg = ExecuteMsg::CreatePot(CreatePotMsg {
+            target_addr: None,
+            recipients: None,
+            threshold: Uint128::new(100),
+            overshoot: None,
+            recurring: None,
+            milestones: Some(milestones),
+            approvers: None,
+            approval_threshold: None,
+            receipt_min: None,
+            vesting_duration: None,
+        });
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let send = |amount: u128| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: String::from("donor"),
+                amount: Uint128::new(amount),
+                msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(1) }).unwrap(),
+            })
+        };
+        let transfer = |recipient: &str, amount: u128| {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("cw20"),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: String::from(recipient),
+                    amount: Uint128::new(amount),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        };
+
+        // below the first milestone nothing is released
+        let res = execute(deps.as_mut(), mock_env(), mock_info("cw20", &[]), send(20)).unwrap();
+        assert_eq!(res.messages.len(), 0);
+
+        // crossing first milestone releases its tranche
+        let res = execute(deps.as_mut(), mock_env(), mock_info("cw20", &[]), send(40)).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(res.messages[0].msg, transfer("design", 30));
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetPot { id: Uint64::new(1) },
+        )
+        .unwrap();
+        let pot: PotResponse = from_binary(&res).unwrap();
+        assert_eq!(pot.status, PotStatus::Open);
+        assert_eq!(
+            pot.milestones
+                .iter()
+                .map(|m| m.released)
+                .collect::<Vec<_>>(),
+            vec![true, false, false]
+        );
+
+        // crossing the rest releases them in order, the last one takes the overshoot
+        let res = execute(deps.as_mut(), mock_env(), mock_info("cw20", &[]), send(45)).unwrap();
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(res.messages[0].msg, transfer("build", 50));
+        assert_eq!(res.messages[1].msg, transfer("launch", 25));
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetPot { id: Uint64::new(1) },
+        )
+        .unwrap();
+        let pot: PotResponse = from_binary(&res).unwrap();
+        assert_eq!(pot.status, PotStatus::Paid);
+        assert!(pot.milestones.iter().all(|m| m.released));
+    }
+
+    #[test]
+    fn cancel_milestone_pot_refunds_remaining() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePot(CreatePotMsg {
+            target_addr: None,
+            recipients: None,
+            threshold: Uint128::new(100),
+            overshoot: None,
+            recurring: None,
+            milestones: Some(vec![
+                MilestoneMsg {
+                    amount: Uint128::new(50),
+                    recipient: String::from("design"),
+                },
+                MilestoneMsg {
+                    amount: Uint128::new(50),
+                    recipient: String::from("build"),
+                },
+            ]),
+            approvers: None,
+            approval_threshold: None,
+            receipt_min: None,
+            vesting_duration: None,
+        });
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        for (donor, amount) in [("alice", 45u128), ("bob", 30)] {
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: String::from(donor),
+                amount: Uint128::new(amount),
+                msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(1) }).unwrap(),
+            });
+            let _res = execute(deps.as_mut(), mock_env(), mock_info("cw20", &[]), msg).unwrap();
+        }
+
+        // 50 of 75 were released, the remaining 25 is refunded pro rata
+        let msg = ExecuteMsg::CancelPot { id: Uint64::new(1) };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let expected = vec![("alice", 15u128), ("bob", 10)];
+        assert_eq!(res.messages.len(), expected.len());
+        for (msg, (recipient, amount)) in res.messages.iter().zip(expected) {
+            assert_eq!(
+                msg.msg,
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: String::from("cw20"),
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: String::from(recipient),
+                        amount: Uint128::new(amount)
+                    })
+                    .unwrap(),
+                    funds: vec![]
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn approval_gated_payout() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // 2 of 3 approvers
+        let msg = ExecuteMsg::CreatePot(CreatePotMsg {
+            target_addr: Some(String::from("Some")),
+            recipients: None,
+            threshold: Uint128::new(100),
+            overshoot: None,
+            recurring: None,
+            milestones: None,
+            approvers: Some(vec![
+                String::from("alice"),
+                String::from("bob"),
+                String::from("carol"),
+            ]),
+            approval_threshold: Some(2),
+            receipt_min: None,
+            vesting_duration: None,
+        });
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // cannot approve before the threshold is reached
+        let approve = ExecuteMsg::ApprovePayout { id: Uint64::new(1) };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            approve.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::NotAwaitingApproval {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // reaching the threshold does not pay out
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("donor"),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(1) }).unwrap(),
+        });
+        let res = execute(deps.as_mut(), mock_env(), mock_info("cw20", &[]), msg).unwrap();
+        assert_eq!(res.messages.len(), 0);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetPot { id: Uint64::new(1) },
+        )
+        .unwrap();
+        let pot: PotResponse = from_binary(&res).unwrap();
+        assert_eq!(pot.status, PotStatus::AwaitingApproval);
+
+        // random cannot approve
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            approve.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            approve.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 0);
+
+        // cannot vote twice
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            approve.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::AlreadyVoted {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // second approval releases the funds
+        let res = execute(deps.as_mut(), mock_env(), mock_info("carol", &[]), approve).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("cw20"),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: String::from("Some"),
+                    amount: Uint128::new(100)
+                })
+                .unwrap(),
+                funds: vec![]
+            })
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetPot { id: Uint64::new(1) },
+        )
+        .unwrap();
+        let pot: PotResponse = from_binary(&res).unwrap();
+        assert_eq!(pot.status, PotStatus::Paid);
+    }
+
+    #[test]
+    fn rejected_payout_refunds() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // 2 of 3 approvers, two rejections make approval impossible
+        let msg = ExecuteMsg::CreatePot(CreatePotMsg {
+            target_addr: Some(String::from("Some")),
+            recipients: None,
+            threshold: Uint128::new(100),
+            overshoot: None,
+            recurring: None,
+            milestones: None,
+            approvers: Some(vec![
+                String::from("alice"),
+                String::from("bob"),
+                String::from("carol"),
+            ]),
+            approval_threshold: Some(2),
+            receipt_min: None,
+            vesting_duration: None,
+        });
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("donor"),
+            amount: Uint128::new(120),
+            msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(1) }).unwrap(),
+        });
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("cw20", &[]), msg).unwrap();
+
+        let reject = ExecuteMsg::RejectPayout { id: Uint64::new(1) };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            reject.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 0);
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), reject).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("cw20"),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: String::from("donor"),
+                    amount: Uint128::new(120)
+                })
+                .unwrap(),
+                funds: vec![]
+            })
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetPot { id: Uint64::new(1) },
+        )
+        .unwrap();
+        let pot: PotResponse = from_binary(&res).unwrap();
+        assert_eq!(pot.status, PotStatus::Rejected);
+    }
+
+    #[test]
+    fn deposit_from() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = create_pot_msg("Some", 100);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::DepositFrom {
+            id: Uint64::new(1),
+            amount: Uint128::new(100),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("donor", &[]), msg).unwrap();
+
+        // tokens are pulled first, then paid out
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("cw20"),
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: String::from("donor"),
+                    recipient: String::from(MOCK_CONTRACT_ADDR),
+                    amount: Uint128::new(100)
+                })
+                .unwrap(),
+                funds: vec![]
+            })
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("cw20"),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: String::from("Some"),
+                    amount: Uint128::new(100)
+                })
+                .unwrap(),
+                funds: vec![]
+            })
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetPot { id: Uint64::new(1) },
+        )
+        .unwrap();
+        let pot: PotResponse = from_binary(&res).unwrap();
+        assert_eq!(pot.collected, Uint128::new(100));
+        assert_eq!(pot.status, PotStatus::Paid);
+    }
+
+    #[test]
+    fn payout_reply_and_retry() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        for _ in 0..2 {
+            let msg = create_pot_msg("Some", 100);
+            let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        }
+
+        let send = |id: u64| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: String::from("donor"),
+                amount: Uint128::new(100),
+                msg: to_binary(&ReceiveMsg::Send {
+                    id: Uint64::new(id),
+                })
+                .unwrap(),
+            })
+        };
+
+        // payouts are dispatched as submessages replying always
+        let res = execute(deps.as_mut(), mock_env(), mock_info("cw20", &[]), send(1)).unwrap();
+        assert_eq!(res.messages[0].id, 1);
+        assert_eq!(res.messages[0].reply_on, ReplyOn::Always);
+
+        // successful transfer is confirmed
+        let ok = Reply {
+            id: 1,
+            result: SubMsgResult::Ok(SubMsgExecutionResponse {
+                events: vec![],
+                data: None,
+            }),
+        };
+        let _res = reply(deps.as_mut(), mock_env(), ok).unwrap();
+        assert!(PENDING_PAYOUTS
+            .may_load(&deps.storage, 1)
+            .unwrap()
+            .is_none());
+
+        // failed transfer leaves the pot in payout failed
+        let res = execute(deps.as_mut(), mock_env(), mock_info("cw20", &[]), send(2)).unwrap();
+        assert_eq!(res.messages[0].id, 2);
+        let failed = Reply {
+            id: 2,
+            result: SubMsgResult::Err(String::from("Cannot transfer")),
+        };
+        let _res = reply(deps.as_mut(), mock_env(), failed).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetPot { id: Uint64::new(2) },
+        )
+        .unwrap();
+        let pot: PotResponse = from_binary(&res).unwrap();
+        assert_eq!(pot.status, PotStatus::PayoutFailed);
+
+        // only owner can retry, and only pots with failed payouts
+        let retry = |id: u64| ExecuteMsg::RetryPayout {
+            id: Uint64::new(id),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            retry(2),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), retry(1)).unwrap_err();
+        match err {
+            ContractError::NoFailedPayout {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let res = execute(deps.as_mut(), mock_env(), info, retry(2)).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(res.messages[0].id, 3);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("cw20"),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: String::from("Some"),
+                    amount: Uint128::new(100)
+                })
+                .unwrap(),
+                funds: vec![]
+            })
+        );
+        assert!(PENDING_PAYOUTS
+            .may_load(&deps.storage, 2)
+            .unwrap()
+            .is_none());
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetPot { id: Uint64::new(2) },
+        )
+        .unwrap();
+        let pot: PotResponse = from_binary(&res).unwrap();
+        assert_eq!(pot.status, PotStatus::Paid);
+    }
+
+    #[test]
+    fn contribution_receipts() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let create = ExecuteMsg::CreatePot(CreatePotMsg {
+            target_addr: Some(String::from("Some")),
+            recipients: None,
+            threshold: Uint128::new(100),
+            overshoot: None,
+            recurring: None,
+            milestones: None,
+            approvers: None,
+            approval_threshold: None,
+            receipt_min: Some(Uint128::new(10)),
+            vesting_duration: None,
+        });
+
+        // receipts need a receipt contract
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), create.clone()).unwrap_err();
+        match err {
+            ContractError::NoReceiptContract {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+            cw20_addr: None,
+            native_denom: None,
+            public_creation: None,
+            fallback_addr: None,
+            clear_fallback_addr: None,
+            receipt_contract: Some(String::from("receipts")),
+            clear_receipt_contract: None,
+        });
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let _res = execute(deps.as_mut(), mock_env(), info, create).unwrap();
+
+        let send = |amount: u128| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: String::from("donor"),
+                amount: Uint128::new(amount),
+                msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(1) }).unwrap(),
+            })
+        };
+
+        // below the minimum no receipt is minted
+        let res = execute(deps.as_mut(), mock_env(), mock_info("cw20", &[]), send(5)).unwrap();
+        assert_eq!(res.messages.len(), 0);
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("cw20", &[]), send(10)).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("receipts"),
+                msg: to_binary(&ReceiptExecuteMsg::Mint(ReceiptMintMsg {
+                    token_id: String::from("1-1"),
+                    owner: String::from("donor"),
+                    token_uri: None,
+                    extension: ReceiptExtension {
+                        pot_id: Uint64::new(1),
+                        amount: Uint128::new(10),
+                    },
+                }))
+                .unwrap(),
+                funds: vec![]
+            })
+        );
+
+        // deposits keep working without receipts once the receipt contract is cleared
+        let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+            cw20_addr: None,
+            native_denom: None,
+            public_creation: None,
+            fallback_addr: None,
+            clear_fallback_addr: None,
+            receipt_contract: None,
+            clear_receipt_contract: Some(true),
+        });
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("cw20", &[]), send(10)).unwrap();
+        assert_eq!(res.messages.len(), 0);
+    }
+
+    #[test]
+    fn stats() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        for _ in 0..3 {
+            let msg = create_pot_msg("Some", 100);
+            let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        }
+
+        let send = |donor: &str, id: u64, amount: u128| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: String::from(donor),
+                amount: Uint128::new(amount),
+                msg: to_binary(&ReceiveMsg::Send {
+                    id: Uint64::new(id),
+                })
+                .unwrap(),
+            })
+        };
+        let cw20 = mock_info("cw20", &[]);
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            cw20.clone(),
+            send("alice", 1, 60),
+        )
+        .unwrap();
+        let _res = execute(deps.as_mut(), mock_env(), cw20.clone(), send("bob", 1, 40)).unwrap();
+        let _res = execute(deps.as_mut(), mock_env(), cw20, send("alice", 2, 30)).unwrap();
+
+        // confirm payout of the first pot
+        let ok = Reply {
+            id: 1,
+            result: SubMsgResult::Ok(SubMsgExecutionResponse {
+                events: vec![],
+                data: None,
+            }),
+        };
+        let _res = reply(deps.as_mut(), mock_env(), ok).unwrap();
+
+        let msg = ExecuteMsg::CancelPot { id: Uint64::new(3) };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Stats {}).unwrap();
+        let stats: StatsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            stats,
+            Stats {
+                pots: StatusCounts {
+                    open: 1,
+                    paid: 1,
+                    cancelled: 1,
+                    ..StatusCounts::default()
+                },
+                total_collected: Uint128::new(130),
+                total_paid_out: Uint128::new(100),
+                contributors: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn migrate_from_v0_1() {
+        let mut deps = mock_dependencies();
+
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.1.0").unwrap();
+        v0_1::CONFIG
+            .save(
+                deps.as_mut().storage,
+                &v0_1::Config {
+                    owner: Addr::unchecked("creator"),
+                    cw20_addr: Addr::unchecked("cw20"),
+                },
+            )
+            .unwrap();
+        POT_SEQ
+            .save(deps.as_mut().storage, &Uint64::new(2))
+            .unwrap();
+        let old_pot = |collected: u128| v0_1::Pot {
+            target_addr: Addr::unchecked("Some"),
+            threshold_amount: Uint128::new(100),
+            collected: Uint128::new(collected),
+        };
+        v0_1::POTS
+            .save(deps.as_mut().storage, 1, &old_pot(120))
+            .unwrap();
+        v0_1::POTS
+            .save(deps.as_mut().storage, 2, &old_pot(30))
+            .unwrap();
+
+        let msg = MigrateMsg {
+            public_creation: Some(true),
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let _res = migrate(deps.as_mut(), mock_env(), msg).unwrap();
+
+        let version = get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(version.version, CONTRACT_VERSION);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let config: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(config.owner, Addr::unchecked("creator"));
+        assert_eq!(config.asset, Asset::Cw20(Addr::unchecked("cw20")));
+        assert!(config.public_creation);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetPot { id: Uint64::new(1) },
+        )
+        .unwrap();
+        let pot: PotResponse = from_binary(&res).unwrap();
+        assert_eq!(pot.creator, "creator");
+        assert_eq!(
+            pot.recipients,
+            vec![Recipient {
+                addr: Addr::unchecked("Some"),
+                weight: TOTAL_WEIGHT,
+            }]
+        );
+        assert_eq!(pot.status, PotStatus::Paid);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetPot { id: Uint64::new(2) },
+        )
+        .unwrap();
+        let pot: PotResponse = from_binary(&res).unwrap();
+        assert_eq!(pot.status, PotStatus::Open);
+        assert_eq!(pot.collected, Uint128::new(30));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Stats {}).unwrap();
+        let stats: StatsResponse = from_binary(&res).unwrap();
+        assert_eq!(stats.pots.open, 1);
+        assert_eq!(stats.pots.paid, 1);
+        assert_eq!(stats.total_collected, Uint128::new(150));
+        assert_eq!(HOLDING_POTS.load(deps.as_ref().storage).unwrap(), 1);
+
+        // the untracked balance of the open pot cannot be cancelled to the owner
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::CancelPot { id: Uint64::new(2) };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        match err {
+            ContractError::MigratedPot {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // it still pays out to the target once the threshold is reached
+        let info = mock_info("cw20", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("beneficiary"),
+            amount: Uint128::new(70),
+            msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(2) }).unwrap(),
+        });
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            Cw20Contract(Addr::unchecked("cw20"))
+                .call(Cw20ExecuteMsg::Transfer {
+                    recipient: String::from("Some"),
+                    amount: Uint128::new(100),
+                })
+                .unwrap()
+        );
+        assert_eq!(HOLDING_POTS.load(deps.as_ref().storage).unwrap(), 0);
+
+        // migrating again keeps the state
+        let msg = MigrateMsg {
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let _res = migrate(deps.as_mut(), mock_env(), msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+        let config: ConfigResponse = from_binary(&res).unwrap();
+        assert!(config.public_creation);
+    }
+
+    #[test]
+    fn migrate_rejects_other_contracts_and_downgrades() {
+        let mut deps = mock_dependencies();
+        let msg = MigrateMsg {
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+
+        set_contract_version(deps.as_mut().storage, "crates.io:other", "0.1.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), msg.clone()).unwrap_err();
+        match err {
+            ContractError::WrongContract { contract } => assert_eq!(contract, "crates.io:other"),
+            e => panic!("unexpected error: {}", e),
+        }
+
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "9.0.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), msg).unwrap_err();
+        match err {
+            ContractError::CannotDowngrade { version } => assert_eq!(version, "9.0.0"),
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn native_asset_pot() {
+        let mut deps = mock_dependencies();
+
+        // asset has to be exactly one of cw20 and native
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: Some(String::from("ujuno")),
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &[]);
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        match err {
+            ContractError::InvalidAsset {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: None,
+            native_denom: Some(String::from("ujuno")),
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = create_pot_msg("Some", 100);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // cw20 deposits are not accepted
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("alice"),
+            amount: Uint128::new(60),
+            msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(1) }).unwrap(),
+        });
+        let err = execute(deps.as_mut(), mock_env(), mock_info("cw20", &[]), msg).unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+        let msg = ExecuteMsg::DepositFrom {
+            id: Uint64::new(1),
+            amount: Uint128::new(60),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap_err();
+        match err {
+            ContractError::WrongAsset {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // other denoms are rejected
+        let msg = ExecuteMsg::Deposit { id: Uint64::new(1) };
+        let info = mock_info("alice", &coins(60, "uatom"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        match err {
+            ContractError::Payment(_) => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let info = mock_info("alice", &coins(60, "ujuno"));
+        let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        let info = mock_info("bob", &coins(50, "ujuno"));
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(1, res.messages.len());
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("Some"),
+                amount: coins(110, "ujuno"),
+            })
+        );
+        assert_eq!(res.messages[0].reply_on, ReplyOn::Always);
+    }
+
+    #[test]
+    fn vesting_payout() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            admin: None,
+            cw20_addr: Some(String::from("cw20")),
+            native_denom: None,
+            public_creation: None,
+            creation_fee: None,
+            fallback_addr: None,
+            receipt_contract: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        // vesting cannot be combined with recurring pots
+        let msg = ExecuteMsg::CreatePot(CreatePotMsg {
+            target_addr: Some(String::from("Some")),
+            recipients: None,
+            threshold: Uint128::new(100),
+            overshoot: None,
+            recurring: Some(true),
+            milestones: None,
+            approvers: None,
+            approval_threshold: None,
+            receipt_min: None,
+            vesting_duration: Some(100),
+        });
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        match err {
+            ContractError::InvalidVesting {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let msg = ExecuteMsg::CreatePot(CreatePotMsg {
+            target_addr: Some(String::from("Some")),
+            recipients: None,
+            threshold: Uint128::new(100),
+            overshoot: None,
+            recurring: None,
+            milestones: None,
+            approvers: None,
+            approval_threshold: None,
+            receipt_min: None,
+            vesting_duration: Some(100),
+        });
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // reaching the threshold starts vesting instead of paying out
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("donor"),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(1) }).unwrap(),
+        });
+        let res = execute(deps.as_mut(), mock_env(), mock_info("cw20", &[]), msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        let claim = ExecuteMsg::ClaimVested { id: Uint64::new(1) };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("Some", &[]),
+            claim.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::NothingToClaim {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(25);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("other", &[]),
+            claim.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let res = execute(deps.as_mut(), env, mock_info("Some", &[]), claim.clone()).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::reply_always(
+                Cw20Contract(Addr::unchecked("cw20"))
+                    .call(Cw20ExecuteMsg::Transfer {
+                        recipient: String::from("Some"),
+                        amount: Uint128::new(25),
+                    })
+                    .unwrap(),
+                1
+            )]
+        );
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(60);
+        let msg = QueryMsg::VestingStatus { id: Uint64::new(1) };
+        let res = query(deps.as_ref(), env, msg).unwrap();
+        let status: VestingStatusResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            status,
+            VestingStatusResponse {
+                duration: 100,
+                start: Some(mock_env().block.time),
+                end: Some(mock_env().block.time.plus_seconds(100)),
+                total: Uint128::new(100),
+                vested: Uint128::new(60),
+                claimed: Uint128::new(25),
+                claimable: Uint128::new(35),
+            }
+        );
+
+        // everything vested after the duration, the pot is paid
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(200);
+        let res = execute(deps.as_mut(), env, mock_info("Some", &[]), claim).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            Cw20Contract(Addr::unchecked("cw20"))
+                .call(Cw20ExecuteMsg::Transfer {
+                    recipient: String::from("Some"),
+                    amount: Uint128::new(75),
+                })
+                .unwrap()
+        );
+
+        let msg = QueryMsg::GetPot { id: Uint64::new(1) };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let pot: PotResponse = from_binary(&res).unwrap();
+        assert_eq!(pot.status, PotStatus::Paid);
+    }
 }
@@ -1,4 +1,5 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Coin, DivideByZeroError, OverflowError, StdError};
+use cw_utils::PaymentError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,12 +7,97 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("{0}")]
+    DivideByZero(#[from] DivideByZeroError),
+
+    #[error("Semver parsing error: {0}")]
+    SemVer(String),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
-    #[error("Custom Error val: {val:?}")]
-    CustomError{val: String},
+    #[error("Cannot migrate from different contract type: {contract}")]
+    WrongContract { contract: String },
+
+    #[error("Cannot migrate from newer version: {version}")]
+    CannotDowngrade { version: String },
+
+    #[error("Must send exact creation fee: {fee}")]
+    InvalidCreationFee { fee: Coin },
+
+    #[error("Pot threshold must be greater than zero")]
+    ZeroThreshold {},
+
+    #[error("Pot must have either target_addr or a non-empty recipients list")]
+    InvalidRecipients {},
+
+    #[error("Recipient weights must sum up to 10000, got {total}")]
+    InvalidWeights { total: u64 },
+
+    #[error("Milestones must have positive amounts summing up to the threshold and cannot be combined with recipients, recurring or approvers")]
+    InvalidMilestones {},
+
+    #[error("Approvers must be unique and the approval threshold between 1 and their number")]
+    InvalidApprovers {},
+
+    #[error("Pot is not awaiting approval")]
+    NotAwaitingApproval {},
 
+    #[error("Approver already voted")]
+    AlreadyVoted {},
+
+    #[error(
+        "Vesting needs a positive duration and cannot be combined with recurring or milestones"
+    )]
+    InvalidVesting {},
+
+    #[error("Pot is not vesting")]
+    NotVesting {},
+
+    #[error("Nothing vested to claim")]
+    NothingToClaim {},
+
+    #[error("No receipt contract configured")]
+    NoReceiptContract {},
+
+    #[error("Pot has no failed payout")]
+    NoFailedPayout {},
+
+    #[error("Set exactly one of cw20_addr and native_denom, cw20 creation fees need a cw20 asset")]
+    InvalidAsset {},
+
+    #[error("Deposit does not match the configured asset")]
+    WrongAsset {},
+
+    #[error("Pot is not open")]
+    PotNotOpen {},
+
+    #[error("Pots migrated from 0.1.0 cannot be cancelled")]
+    MigratedPot {},
+
+    #[error("Cannot set and clear {field} at once")]
+    ConflictingConfig { field: String },
+
+    #[error("Cannot change asset while pots hold a balance")]
+    PotsHoldBalance {},
+
+    #[error("No ownership transfer pending")]
+    NoPendingOwner {},
+
+    #[error("Custom Error val: {val:?}")]
+    CustomError { val: String },
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
+
+impl From<semver::Error> for ContractError {
+    fn from(err: semver::Error) -> Self {
+        Self::SemVer(err.to_string())
+    }
+}
@@ -2,6 +2,7 @@ pub mod contract;
 mod error;
 pub mod helpers;
 pub mod integration_tests;
+mod migrations;
 pub mod msg;
 pub mod state;
 
@@ -0,0 +1,85 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Addr, Empty, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Item, Map};
+
+use crate::state::{
+    OvershootPolicy, Pot, PotStatus, Recipient, Stats, HOLDING_POTS, MIGRATED_POTS, POTS, STATS,
+    TOTAL_WEIGHT,
+};
+
+/// Storage layout of version 0.1.0
+pub mod v0_1 {
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    pub struct Config {
+        pub owner: Addr,
+        pub cw20_addr: Addr,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    pub struct Pot {
+        pub target_addr: Addr,
+        pub threshold_amount: Uint128,
+        pub collected: Uint128,
+    }
+
+    pub const CONFIG: Item<Config> = Item::new("config");
+    pub const POTS: Map<u64, Pot> = Map::new("pot");
+}
+
+/// Rewrites the 0.1.0 pots into the current layout and rebuilds the stats.
+/// Only the owner could create pots, so the owner becomes creator. Contributions were
+/// not tracked back then and 0.1.0 could not cancel pots, so open pots are marked as
+/// migrated and keep paying out to their target only.
+pub fn migrate_pots_from_v0_1(storage: &mut dyn Storage, owner: &Addr) -> StdResult<()> {
+    let old_pots = v0_1::POTS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut stats = Stats::default();
+    let mut holding = 0;
+    for (id, old_pot) in old_pots {
+        // 0.1.0 paid out as soon as the threshold was reached
+        let status = if old_pot.collected >= old_pot.threshold_amount {
+            PotStatus::Paid
+        } else {
+            PotStatus::Open
+        };
+        let pot = Pot {
+            creator: owner.clone(),
+            recipients: vec![Recipient {
+                addr: old_pot.target_addr,
+                weight: TOTAL_WEIGHT,
+            }],
+            threshold_amount: old_pot.threshold_amount,
+            collected: old_pot.collected,
+            overshoot: OvershootPolicy::Accept,
+            status,
+            recurring: false,
+            cycle: 1,
+            milestones: vec![],
+            approval: None,
+            receipt_min: None,
+            vesting: None,
+        };
+
+        if status == PotStatus::Open {
+            MIGRATED_POTS.save(storage, id, &Empty {})?;
+        }
+        *stats.pots.count_mut(status) += 1;
+        stats.total_collected = stats.total_collected.checked_add(pot.collected)?;
+        if status == PotStatus::Paid {
+            stats.total_paid_out = stats.total_paid_out.checked_add(pot.collected)?;
+        }
+        if pot.holds_balance() {
+            holding += 1;
+        }
+
+        POTS.save(storage, id, &pot)?;
+    }
+    HOLDING_POTS.save(storage, &holding)?;
+    STATS.save(storage, &stats)
+}
@@ -1,29 +1,149 @@
-use cosmwasm_std::{Addr, Uint128, Uint64};
+use cosmwasm_std::{Addr, Timestamp, Uint128, Uint64};
 use cw20::Cw20ReceiveMsg;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::state::{
+    Approval, Asset, CreationFee, Milestone, OvershootPolicy, PotStatus, Recipient, Stats, Vesting,
+};
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub admin: Option<String>,
-    pub cw20_addr: String,
+    /// cw20 token pots are funded with, set either this or `native_denom`
+    pub cw20_addr: Option<String>,
+    /// Native denom pots are funded with
+    pub native_denom: Option<String>,
+    /// Allow anyone to create pots, defaults to owner only
+    pub public_creation: Option<bool>,
+    /// Fee charged to non-owner pot creators, forwarded to the owner
+    pub creation_fee: Option<CreationFee>,
+    /// Receives the funds of cancelled pots instead of refunding contributors
+    pub fallback_addr: Option<String>,
+    /// cw721 contract minting contribution receipts, this contract must be its minter
+    pub receipt_contract: Option<String>,
+}
+
+/// Values for config fields introduced since the migrated version
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {
+    pub public_creation: Option<bool>,
+    pub creation_fee: Option<CreationFee>,
+    pub fallback_addr: Option<String>,
+    pub receipt_contract: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    CreatePot {
-        target_addr: String,
-        threshold: Uint128,
-    },
+    CreatePot(CreatePotMsg),
     Receive(Cw20ReceiveMsg),
+    /// Owner can change the config, the asset only while no pot holds a balance
+    UpdateConfig(UpdateConfigMsg),
+    /// Owner proposes a new owner, who has to accept it
+    TransferOwnership {
+        new_owner: String,
+    },
+    /// Pending owner accepts the ownership
+    AcceptOwnership {},
+    /// Owner or pot creator closes an open pot, collected funds are refunded
+    /// to contributors or sent to the fallback address
+    CancelPot {
+        id: Uint64,
+    },
+    /// Approver signs off the payout of a pot awaiting approval
+    ApprovePayout {
+        id: Uint64,
+    },
+    /// Approver rejects the payout, once it cannot be approved contributors are refunded
+    RejectPayout {
+        id: Uint64,
+    },
+    /// Deposit into a pot by pulling tokens with `TransferFrom`,
+    /// needs a prior `IncreaseAllowance` for this contract
+    DepositFrom {
+        id: Uint64,
+        amount: Uint128,
+    },
+    /// Deposit native funds sent along in `info.funds` into a pot
+    Deposit {
+        id: Uint64,
+    },
+    /// Recipient of a vesting pot pays out the vested funds not claimed yet
+    ClaimVested {
+        id: Uint64,
+    },
+    /// Owner dispatches the failed payout transfers of a pot again
+    RetryPayout {
+        id: Uint64,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UpdateConfigMsg {
+    pub cw20_addr: Option<String>,
+    pub native_denom: Option<String>,
+    pub public_creation: Option<bool>,
+    pub fallback_addr: Option<String>,
+    /// Unset the fallback address so cancelled pots refund contributors again
+    pub clear_fallback_addr: Option<bool>,
+    pub receipt_contract: Option<String>,
+    /// Unset the receipt contract, pots with a receipt minimum stop minting receipts
+    pub clear_receipt_contract: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CreatePotMsg {
+    /// Single payout address, set either this or `recipients`
+    pub target_addr: Option<String>,
+    /// Split payout, weights in basis points must sum up to 10000
+    pub recipients: Option<Vec<RecipientMsg>>,
+    pub threshold: Uint128,
+    /// What to do with deposits above the threshold, defaults to accept
+    pub overshoot: Option<OvershootPolicy>,
+    /// Reopen the pot for a new cycle after each payout
+    pub recurring: Option<bool>,
+    /// Ordered tranches summing up to the threshold, replaces target_addr and recipients
+    pub milestones: Option<Vec<MilestoneMsg>>,
+    /// Addresses that have to approve the payout once the threshold is reached
+    pub approvers: Option<Vec<String>>,
+    /// Number of approvals needed, defaults to all approvers
+    pub approval_threshold: Option<u32>,
+    /// Mint a receipt token for contributions of at least this amount
+    pub receipt_min: Option<Uint128>,
+    /// Stream the payout linearly over this many seconds once the threshold is reached
+    pub vesting_duration: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MilestoneMsg {
+    pub amount: Uint128,
+    pub recipient: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RecipientMsg {
+    pub addr: String,
+    pub weight: u16,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     GetConfig {},
-    GetPot { id: Uint64 },
+    GetPot {
+        id: Uint64,
+    },
+    /// Current cycle and payout heights of past cycles
+    PotCycles {
+        id: Uint64,
+    },
+    /// Pot counts by status and contract wide totals
+    Stats {},
+    /// Vested and claimed amounts of a vesting pot
+    VestingStatus {
+        id: Uint64,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -31,16 +151,71 @@ pub enum ReceiveMsg {
     Send { id: Uint64 },
 }
 
+/// Mint message of the receipt cw721 contract, matches cw721-base `ExecuteMsg::Mint`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiptExecuteMsg {
+    Mint(ReceiptMintMsg),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReceiptMintMsg {
+    pub token_id: String,
+    pub owner: String,
+    pub token_uri: Option<String>,
+    pub extension: ReceiptExtension,
+}
+
+/// Metadata of a receipt token
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReceiptExtension {
+    pub pot_id: Uint64,
+    pub amount: Uint128,
+}
+
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigResponse {
     pub owner: Addr,
-    pub cw20_addr: Addr,
+    pub asset: Asset,
+    pub public_creation: bool,
+    pub creation_fee: Option<CreationFee>,
+    pub pending_owner: Option<Addr>,
+    pub fallback_addr: Option<Addr>,
+    pub receipt_contract: Option<Addr>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct PotResponse {
-    pub target_addr: String,
+    pub creator: String,
+    pub recipients: Vec<Recipient>,
     pub threshold: Uint128,
     pub collected: Uint128,
+    pub overshoot: OvershootPolicy,
+    pub status: PotStatus,
+    pub recurring: bool,
+    pub cycle: u64,
+    pub milestones: Vec<Milestone>,
+    pub approval: Option<Approval>,
+    pub receipt_min: Option<Uint128>,
+    pub vesting: Option<Vesting>,
+}
+
+pub type StatsResponse = Stats;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PotCyclesResponse {
+    pub cycle: u64,
+    pub payout_heights: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VestingStatusResponse {
+    pub duration: u64,
+    pub start: Option<Timestamp>,
+    pub end: Option<Timestamp>,
+    pub total: Uint128,
+    pub vested: Uint128,
+    pub claimed: Uint128,
+    pub claimable: Uint128,
 }
@@ -1,20 +1,269 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, DepsMut, StdResult, Uint128, Uint64};
+use cosmwasm_std::{
+    Addr, BankMsg, Coin, CosmosMsg, DepsMut, Empty, StdResult, Storage, Timestamp, Uint128, Uint64,
+};
+use cw20::{Cw20Contract, Cw20ExecuteMsg};
 use cw_storage_plus::{Item, Map};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub owner: Addr,
-    pub cw20_addr: Addr,
+    /// Token pots are funded with and paid out in
+    pub asset: Asset,
+    /// If true anyone can create a pot, otherwise only the owner
+    pub public_creation: bool,
+    /// Fee paid to the owner by non-owner pot creators
+    pub creation_fee: Option<CreationFee>,
+    /// Address proposed by the owner, becomes owner once it accepts
+    pub pending_owner: Option<Addr>,
+    /// Receives the funds of cancelled pots, contributors are refunded if not set
+    pub fallback_addr: Option<Addr>,
+    /// cw721 contract minting contribution receipts, this contract must be its minter
+    pub receipt_contract: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Asset {
+    /// Native denom, deposited with `Deposit` and paid out with `BankMsg::Send`
+    Native(String),
+    /// cw20 token contract, deposited with `Send` or `DepositFrom`
+    Cw20(Addr),
+}
+
+impl Asset {
+    /// Builds the message sending amount of the asset to recipient
+    pub fn transfer_msg(
+        &self,
+        recipient: impl Into<String>,
+        amount: Uint128,
+    ) -> StdResult<CosmosMsg> {
+        match self {
+            Asset::Native(denom) => Ok(BankMsg::Send {
+                to_address: recipient.into(),
+                amount: vec![Coin {
+                    denom: denom.clone(),
+                    amount,
+                }],
+            }
+            .into()),
+            Asset::Cw20(addr) => Cw20Contract(addr.clone()).call(Cw20ExecuteMsg::Transfer {
+                recipient: recipient.into(),
+                amount,
+            }),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CreationFee {
+    /// Sent along with `CreatePot` in `info.funds`
+    Native(Coin),
+    /// Amount of the configured cw20 asset, pulled with `TransferFrom`,
+    /// so the creator must `IncreaseAllowance` for this contract first
+    Cw20(Uint128),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Pot {
-    pub target_addr: Addr,
+    pub creator: Addr,
+    /// Payout split, weights sum up to `TOTAL_WEIGHT`
+    pub recipients: Vec<Recipient>,
     pub threshold_amount: Uint128,
     pub collected: Uint128,
+    pub overshoot: OvershootPolicy,
+    pub status: PotStatus,
+    /// Recurring pots reopen after each payout instead of closing
+    pub recurring: bool,
+    /// Current cycle, starting at 1
+    pub cycle: u64,
+    /// Ordered tranches released as collected crosses them, used instead of recipients
+    pub milestones: Vec<Milestone>,
+    /// Approvers that have to sign off the payout once the threshold is reached
+    pub approval: Option<Approval>,
+    /// Contributions of at least this amount get a receipt token, no receipts if not set
+    pub receipt_min: Option<Uint128>,
+    /// Stream the payout to recipients instead of sending it at once
+    pub vesting: Option<Vesting>,
+}
+
+impl Pot {
+    /// True until the pot is paid out, cancelled or rejected
+    pub fn is_active(&self) -> bool {
+        matches!(self.status, PotStatus::Open | PotStatus::AwaitingApproval)
+    }
+
+    /// True while the pot keeps deposits that were not paid out yet
+    pub fn holds_balance(&self) -> bool {
+        (self.is_active() && self.collected > self.released())
+            || matches!(self.status, PotStatus::Vesting | PotStatus::PayoutFailed)
+    }
+
+    /// Status of the pot once its dispatched payouts went through
+    pub fn settled_status(&self) -> PotStatus {
+        if matches!(&self.vesting, Some(vesting) if vesting.claimed < self.collected) {
+            PotStatus::Vesting
+        } else if self.recurring || self.milestones.iter().any(|m| !m.released) {
+            PotStatus::Open
+        } else {
+            PotStatus::Paid
+        }
+    }
+
+    /// Sum of milestone tranches already paid out
+    pub fn released(&self) -> Uint128 {
+        self.milestones
+            .iter()
+            .filter(|m| m.released)
+            .map(|m| m.amount)
+            .sum()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Milestone {
+    /// Tranche size, the milestone is reached once the sum of tranches up to it is collected
+    pub amount: Uint128,
+    pub recipient: Addr,
+    pub released: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Vesting {
+    /// Seconds over which the collected funds vest linearly
+    pub duration: u64,
+    /// Set once the threshold is reached
+    pub start: Option<Timestamp>,
+    /// Part of the collected funds already paid out to recipients
+    pub claimed: Uint128,
+}
+
+impl Vesting {
+    /// Part of total vested at the given time, nothing before the start
+    pub fn vested(&self, total: Uint128, now: Timestamp) -> Uint128 {
+        match self.start {
+            Some(start) => {
+                let elapsed = now.seconds().saturating_sub(start.seconds());
+                total.multiply_ratio(elapsed.min(self.duration), self.duration)
+            }
+            None => Uint128::zero(),
+        }
+    }
+
+    pub fn end(&self) -> Option<Timestamp> {
+        self.start.map(|start| start.plus_seconds(self.duration))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Approval {
+    pub approvers: Vec<Addr>,
+    /// Number of approvals needed to release the payout
+    pub required: u32,
+    /// Votes of the current payout
+    pub approved: Vec<Addr>,
+    pub rejected: Vec<Addr>,
+}
+
+impl Approval {
+    pub fn has_voted(&self, addr: &Addr) -> bool {
+        self.approved.contains(addr) || self.rejected.contains(addr)
+    }
+
+    pub fn is_approved(&self) -> bool {
+        self.approved.len() >= self.required as usize
+    }
+
+    /// Rejected once the remaining approvers cannot reach the required approvals
+    pub fn is_rejected(&self) -> bool {
+        self.rejected.len() > self.approvers.len() - self.required as usize
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PotStatus {
+    /// Accepting deposits
+    Open,
+    /// Threshold reached, waiting for approvers to release the payout
+    AwaitingApproval,
+    /// Threshold reached, collected funds vest and are claimed by recipients
+    Vesting,
+    /// Threshold reached and collected funds paid out
+    Paid,
+    /// Closed by the owner or creator before reaching the threshold
+    Cancelled,
+    /// Payout rejected by approvers, contributors were refunded
+    Rejected,
+    /// A payout transfer failed, the owner can retry it
+    PayoutFailed,
+}
+
+/// Payout transfer waiting for its reply
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingPayout {
+    pub pot_id: u64,
+    pub recipient: Addr,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OvershootPolicy {
+    /// Keep deposits above the threshold and pay them out with the pot
+    Accept,
+    /// Cap the pot at the threshold and return the excess to the sender
+    Refund,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Recipient {
+    pub addr: Addr,
+    /// Share of the payout in basis points
+    pub weight: u16,
+}
+
+/// Recipient weights of a pot must sum up to 10000 basis points
+pub const TOTAL_WEIGHT: u16 = 10_000;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct Stats {
+    pub pots: StatusCounts,
+    /// Sum of all accepted deposits
+    pub total_collected: Uint128,
+    /// Sum of all confirmed payout transfers
+    pub total_paid_out: Uint128,
+    /// Number of distinct addresses that ever contributed
+    pub contributors: u64,
+}
+
+/// Number of pots in each status
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct StatusCounts {
+    pub open: u64,
+    pub awaiting_approval: u64,
+    pub vesting: u64,
+    pub paid: u64,
+    pub cancelled: u64,
+    pub rejected: u64,
+    pub payout_failed: u64,
+}
+
+impl StatusCounts {
+    pub fn count_mut(&mut self, status: PotStatus) -> &mut u64 {
+        match status {
+            PotStatus::Open => &mut self.open,
+            PotStatus::AwaitingApproval => &mut self.awaiting_approval,
+            PotStatus::Vesting => &mut self.vesting,
+            PotStatus::Paid => &mut self.paid,
+            PotStatus::Cancelled => &mut self.cancelled,
+            PotStatus::Rejected => &mut self.rejected,
+            PotStatus::PayoutFailed => &mut self.payout_failed,
+        }
+    }
 }
 
 pub fn save_pot(deps: DepsMut, pot: &Pot) -> StdResult<()> {
@@ -24,9 +273,54 @@ pub fn save_pot(deps: DepsMut, pot: &Pot) -> StdResult<()> {
     let id = id.checked_add(Uint64::new(1))?;
     POT_SEQ.save(deps.storage, &id)?;
 
-    POTS.save(deps.storage, id.u64().into(), pot)
+    STATS.update(deps.storage, |mut stats| -> StdResult<_> {
+        *stats.pots.count_mut(pot.status) += 1;
+        Ok(stats)
+    })?;
+
+    POTS.save(deps.storage, id.u64(), pot)
+}
+
+/// Saves an existing pot, moving it between the status counters of `STATS`
+/// and keeping `HOLDING_POTS` in sync
+pub fn update_pot(storage: &mut dyn Storage, id: u64, pot: &Pot) -> StdResult<()> {
+    let previous = POTS.load(storage, id)?;
+    if previous.status != pot.status {
+        STATS.update(storage, |mut stats| -> StdResult<_> {
+            *stats.pots.count_mut(previous.status) -= 1;
+            *stats.pots.count_mut(pot.status) += 1;
+            Ok(stats)
+        })?;
+    }
+    if previous.holds_balance() != pot.holds_balance() {
+        HOLDING_POTS.update(storage, |count| -> StdResult<_> {
+            Ok(if pot.holds_balance() {
+                count + 1
+            } else {
+                count - 1
+            })
+        })?;
+    }
+    POTS.save(storage, id, pot)
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const POT_SEQ: Item<Uint64> = Item::new("pot_seq");
+pub const STATS: Item<Stats> = Item::new("stats");
+/// Number of pots holding deposits that were not paid out, the asset can only be switched at zero
+pub const HOLDING_POTS: Item<u64> = Item::new("holding_pots");
+/// Every address that contributed to any pot
+pub const CONTRIBUTORS: Map<&Addr, Empty> = Map::new("contributors");
 pub const POTS: Map<u64, Pot> = Map::new("pot");
+/// Amount each address deposited into a pot, used for refunds
+pub const CONTRIBUTIONS: Map<(u64, &Addr), Uint128> = Map::new("contributions");
+/// Open pots migrated from 0.1.0, their deposits have no contributor records to refund
+pub const MIGRATED_POTS: Map<u64, Empty> = Map::new("migrated_pots");
+/// Block height of each payout, keyed by pot id and cycle
+pub const PAYOUT_HEIGHTS: Map<(u64, u64), u64> = Map::new("payout_heights");
+/// Last receipt token number, used to build unique token ids
+pub const RECEIPT_SEQ: Item<u64> = Item::new("receipt_seq");
+/// Last reply id used for payout submessages
+pub const PAYOUT_SEQ: Item<u64> = Item::new("payout_seq");
+/// Payout transfers by reply id, removed once confirmed
+pub const PENDING_PAYOUTS: Map<u64, PendingPayout> = Map::new("pending_payouts");
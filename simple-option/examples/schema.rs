@@ -3,7 +3,7 @@ use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, export_schema_with_title, remove_schemas, schema_for};
 
-use simple_option::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use simple_option::msg::{ExecuteMsg, InstantiateMsg, OptionResponse, QueryMsg};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -14,5 +14,5 @@ fn main() {
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
-    export_schema_with_title(&mut schema_for!(ConfigResponse), &out_dir, "ConfigResponse");
+    export_schema_with_title(&mut schema_for!(OptionResponse), &out_dir, "OptionResponse");
 }
@@ -1,18 +1,39 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    from_binary, to_binary, Addr, Api, BankMsg, Binary, BlockInfo, Coin, CosmosMsg, Deps, DepsMut,
+    Env, MessageInfo, Order, Response, StdError, StdResult, Uint128, WasmMsg,
 };
 use cw2::set_contract_version;
+use cw20::{Balance, Cw20CoinVerified, Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw721::{
+    AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, ContractInfoResponse, Cw721ReceiveMsg,
+    NumTokensResponse, OperatorsResponse, OwnerOfResponse, TokensResponse,
+};
+use cw_storage_plus::Bound;
+use cw_utils::{maybe_addr, nonpayable, Expiration, NativeBalance};
 
 use crate::error::ContractError;
-use crate::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{State, STATE};
+use crate::msg::{
+    CreateOptionMsg, ExecuteMsg, InstantiateMsg, OptionExtension, OptionNftInfoResponse,
+    OptionResponse, QueryMsg, ReceiveMsg,
+};
+use crate::state::{
+    options, remove_option, save_option, Approval, OptionKind, State, CONTRACT_INFO, NUM_TOKENS,
+    OPERATORS,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:simple-option";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+const DEFAULT_NAME: &str = "Simple Option";
+const DEFAULT_SYMBOL: &str = "OPTION";
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -20,22 +41,30 @@ pub fn instantiate(
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
-    if msg.expires <= env.block.height {
-        return Err(ContractError::CreateExpired {});
-    }
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    CONTRACT_INFO.save(
+        deps.storage,
+        &ContractInfoResponse {
+            name: msg.name.unwrap_or_else(|| DEFAULT_NAME.to_string()),
+            symbol: msg.symbol.unwrap_or_else(|| DEFAULT_SYMBOL.to_string()),
+        },
+    )?;
 
-    let state = State {
-        creator: info.sender.clone(),
-        owner: info.sender.clone(),
-        collateral: info.funds,
-        counter_offer: msg.counter_offer,
-        expires: msg.expires,
+    let res = Response::new().add_attribute("method", "instantiate");
+    let terms = match msg.option {
+        Some(terms) => terms,
+        None => {
+            nonpayable(&info)?;
+            return Ok(res);
+        }
     };
-    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    STATE.save(deps.storage, &state)?;
+    let sent = normalize_balance(Balance::from(info.funds));
+    let state = new_option(deps.api, &env, info.sender.clone(), sent, terms)?;
+    let id = save_option(deps.storage, &state)?;
 
-    Ok(Response::new()
-        .add_attribute("method", "instantiate")
+    Ok(res
+        .add_attribute("id", id.to_string())
+        .add_attribute("kind", state.kind.as_str())
         .add_attribute("creator", info.sender.clone())
         .add_attribute("owner", info.sender))
 }
@@ -48,78 +77,593 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Transfer { recipient } => handle_transfer(deps, info, recipient),
-        ExecuteMsg::Execute {} => handle_execute(deps, env, info),
-        ExecuteMsg::Burn {} => handle_burn(deps, env, info),
+        ExecuteMsg::CreateOption(msg) => handle_create_option(deps, env, info, msg),
+        ExecuteMsg::Receive(msg) => handle_receive(deps, env, info, msg),
+        ExecuteMsg::TransferNft {
+            recipient,
+            token_id,
+        } => handle_transfer(deps, env, info, recipient, token_id),
+        ExecuteMsg::SendNft {
+            contract,
+            token_id,
+            msg,
+        } => handle_send(deps, env, info, contract, token_id, msg),
+        ExecuteMsg::Approve {
+            spender,
+            token_id,
+            expires,
+        } => handle_approve(deps, env, info, spender, token_id, expires),
+        ExecuteMsg::Revoke { spender, token_id } => {
+            handle_revoke(deps, env, info, spender, token_id)
+        }
+        ExecuteMsg::ApproveAll { operator, expires } => {
+            handle_approve_all(deps, env, info, operator, expires)
+        }
+        ExecuteMsg::RevokeAll { operator } => handle_revoke_all(deps, info, operator),
+        ExecuteMsg::Execute { id } => handle_execute(deps, env, info, id),
+        ExecuteMsg::Burn { id } => handle_burn(deps, env, info, id),
+        ExecuteMsg::Cancel { id } => handle_cancel(deps, info, id),
+        ExecuteMsg::AddCollateral { id } => handle_add_collateral(deps, env, info, id),
+        ExecuteMsg::UpdateCounterOffer { id, counter_offer } => {
+            handle_update_counter_offer(deps, env, info, id, counter_offer)
+        }
+        ExecuteMsg::ListForSale { id, price } => handle_list_for_sale(deps, env, info, id, price),
+        ExecuteMsg::CancelListing { id } => handle_cancel_listing(deps, info, id),
+        ExecuteMsg::Buy { id } => handle_buy(deps, env, info, id),
+    }
+}
+
+/// Options expire at a height or a time that has not passed yet
+fn validate_expires(env: &Env, expires: &Expiration) -> Result<(), ContractError> {
+    if let Expiration::Never {} = expires {
+        return Err(ContractError::NeverExpires {});
+    }
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::CreateExpired {});
+    }
+    Ok(())
+}
+
+/// Native coins sorted by denom, with duplicate denoms merged and zero amounts dropped,
+/// so equal funds compare equal whatever order they were sent in
+fn normalize_balance(balance: Balance) -> Balance {
+    match balance {
+        Balance::Native(mut coins) => {
+            coins.normalize();
+            Balance::Native(coins)
+        }
+        cw20 => cw20,
+    }
+}
+
+fn normalize_coins(coins: Vec<Coin>) -> Vec<Coin> {
+    let mut coins = NativeBalance(coins);
+    coins.normalize();
+    coins.into_vec()
+}
+
+/// cw20 addresses in user supplied balances are validated, native coins normalized.
+/// Zero cw20 amounts are rejected, cw20 cannot send them to exercise the option.
+fn validate_balance(api: &dyn Api, balance: Balance) -> Result<Balance, ContractError> {
+    match balance {
+        Balance::Native(coins) => Ok(normalize_balance(Balance::Native(coins))),
+        Balance::Cw20(coin) if coin.amount.is_zero() => Err(ContractError::ZeroCw20Amount {}),
+        Balance::Cw20(coin) => Ok(Balance::Cw20(Cw20CoinVerified {
+            address: api.addr_validate(coin.address.as_str())?,
+            amount: coin.amount,
+        })),
+    }
+}
+
+/// Builds the bank or cw20 transfer sending balance to recipient, nothing for empty balances
+fn transfer_msgs(recipient: &Addr, balance: Balance) -> StdResult<Vec<CosmosMsg>> {
+    match normalize_balance(balance) {
+        balance if balance.is_empty() => Ok(vec![]),
+        Balance::Native(coins) => Ok(vec![BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: coins.into_vec(),
+        }
+        .into()]),
+        Balance::Cw20(coin) => Ok(vec![WasmMsg::Execute {
+            contract_addr: coin.address.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: coin.amount,
+            })?,
+            funds: vec![],
+        }
+        .into()]),
+    }
+}
+
+/// Share of the counter_offer covered by sent, as paid and total amount of its first asset.
+/// None unless every asset of the counter_offer is paid in the same proportion.
+fn exercised_share(sent: &Balance, counter_offer: &Balance) -> Option<(Uint128, Uint128)> {
+    let pairs: Vec<(Uint128, Uint128)> = match (sent, counter_offer) {
+        (Balance::Native(sent), Balance::Native(total)) => {
+            if sent.0.len() != total.0.len() {
+                return None;
+            }
+            total
+                .0
+                .iter()
+                .map(|t| {
+                    let s = sent.0.iter().find(|s| s.denom == t.denom)?;
+                    Some((s.amount, t.amount))
+                })
+                .collect::<Option<_>>()?
+        }
+        (Balance::Cw20(sent), Balance::Cw20(total)) if sent.address == total.address => {
+            vec![(sent.amount, total.amount)]
+        }
+        _ => return None,
+    };
+
+    let (paid, total) = *pairs.first()?;
+    if paid.is_zero() || paid > total {
+        return None;
+    }
+    pairs
+        .iter()
+        .all(|(s, t)| s.full_mul(total) == t.full_mul(paid))
+        .then_some((paid, total))
+}
+
+/// Scales every asset by paid / total, rounding down, denoms scaled to zero are dropped
+fn scale_balance(balance: &Balance, paid: Uint128, total: Uint128) -> Balance {
+    match balance {
+        Balance::Native(coins) => normalize_balance(Balance::Native(NativeBalance(
+            coins
+                .0
+                .iter()
+                .map(|c| Coin {
+                    denom: c.denom.clone(),
+                    amount: c.amount.multiply_ratio(paid, total),
+                })
+                .collect(),
+        ))),
+        Balance::Cw20(coin) => Balance::Cw20(Cw20CoinVerified {
+            address: coin.address.clone(),
+            amount: coin.amount.multiply_ratio(paid, total),
+        }),
+    }
+}
+
+/// Subtracts part from balance, both must hold the same assets
+fn subtract_balance(balance: Balance, part: &Balance) -> StdResult<Balance> {
+    match (balance, part) {
+        (Balance::Native(mut coins), Balance::Native(part)) => {
+            for coin in part.0.iter() {
+                coins = coins.sub_saturating(coin.clone())?;
+            }
+            Ok(Balance::Native(coins))
+        }
+        (Balance::Cw20(mut coin), Balance::Cw20(part)) => {
+            coin.amount = coin.amount.checked_sub(part.amount)?;
+            Ok(Balance::Cw20(coin))
+        }
+        _ => Err(StdError::generic_err("Mismatched assets")),
+    }
+}
+
+/// Adds extra to balance, extra must only hold denoms of balance or the same cw20 token
+fn add_balance(balance: Balance, extra: Balance) -> Result<Balance, ContractError> {
+    match (balance, extra) {
+        (Balance::Native(mut coins), Balance::Native(extra)) => {
+            for c in extra.into_vec() {
+                let o = coins
+                    .0
+                    .iter_mut()
+                    .find(|o| o.denom == c.denom)
+                    .ok_or(ContractError::InvalidCollateral {})?;
+                o.amount = o.amount.checked_add(c.amount).map_err(StdError::from)?;
+            }
+            Ok(normalize_balance(Balance::Native(coins)))
+        }
+        (Balance::Cw20(mut coin), Balance::Cw20(extra)) if coin.address == extra.address => {
+            coin.amount = coin
+                .amount
+                .checked_add(extra.amount)
+                .map_err(StdError::from)?;
+            Ok(Balance::Cw20(coin))
+        }
+        _ => Err(ContractError::InvalidCollateral {}),
+    }
+}
+
+/// True if new asks no asset the old balance doesn't and no more of any asset
+fn is_lower_balance(new: &Balance, old: &Balance) -> bool {
+    match (new, old) {
+        (Balance::Native(new), Balance::Native(old)) => new.0.iter().all(|c| {
+            old.0
+                .iter()
+                .any(|o| o.denom == c.denom && o.amount >= c.amount)
+        }),
+        (Balance::Cw20(new), Balance::Cw20(old)) => {
+            new.address == old.address && new.amount <= old.amount
+        }
+        _ => false,
+    }
+}
+
+/// Readable form of a balance for attributes
+fn describe_balance(balance: &Balance) -> String {
+    match balance {
+        Balance::Native(coins) => coins
+            .0
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+            .join(","),
+        Balance::Cw20(coin) => format!("{}{}", coin.amount, coin.address),
+    }
+}
+
+/// Builds an unsold option, sent has to be the side of the terms the kind locks
+fn new_option(
+    api: &dyn Api,
+    env: &Env,
+    creator: Addr,
+    sent: Balance,
+    msg: CreateOptionMsg,
+) -> Result<State, ContractError> {
+    validate_expires(env, &msg.expires)?;
+
+    let kind = msg.kind.unwrap_or(OptionKind::Call);
+    let (collateral, counter_offer) = kind.orient(
+        validate_balance(api, msg.underlying)?,
+        validate_balance(api, msg.strike)?,
+    );
+    if sent != collateral {
+        return Err(ContractError::WrongCollateral {
+            side: kind.locked().to_string(),
+            expected: collateral,
+            received: sent,
+        });
+    }
+
+    Ok(State {
+        creator: creator.clone(),
+        owner: creator,
+        kind,
+        collateral,
+        counter_offer,
+        expires: msg.expires,
+        approvals: vec![],
+        price: None,
+    })
+}
+
+pub fn handle_create_option(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: CreateOptionMsg,
+) -> Result<Response, ContractError> {
+    let sent = normalize_balance(Balance::from(info.funds));
+    create_option(deps, env, info.sender, sent, msg)
+}
+
+pub fn handle_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    wrapped: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    // the sending contract is the token
+    let sent = Balance::Cw20(Cw20CoinVerified {
+        address: info.sender,
+        amount: wrapped.amount,
+    });
+    let sender = deps.api.addr_validate(&wrapped.sender)?;
+
+    let msg: ReceiveMsg = from_binary(&wrapped.msg)?;
+    match msg {
+        ReceiveMsg::CreateOption(msg) => create_option(deps, env, sender, sent, msg),
+        ReceiveMsg::Execute { id } => execute_option(deps, env, sender, sent, id),
+        ReceiveMsg::AddCollateral { id } => add_collateral(deps, env, sender, sent, id),
+    }
+}
+
+fn create_option(
+    deps: DepsMut,
+    env: Env,
+    creator: Addr,
+    sent: Balance,
+    msg: CreateOptionMsg,
+) -> Result<Response, ContractError> {
+    let state = new_option(deps.api, &env, creator.clone(), sent, msg)?;
+    let kind = state.kind;
+    let id = save_option(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "handle_create_option")
+        .add_attribute("id", id.to_string())
+        .add_attribute("kind", kind.as_str())
+        .add_attribute("creator", creator))
+}
+
+/// Token ids of the cw721 interface are option ids
+fn parse_token_id(token_id: &str) -> StdResult<u64> {
+    token_id
+        .parse()
+        .map_err(|_| StdError::parse_err("u64", format!("invalid token_id {}", token_id)))
+}
+
+/// Owner or an operator of the owner
+fn check_can_approve(
+    deps: Deps,
+    block: &BlockInfo,
+    sender: &Addr,
+    state: &State,
+) -> Result<(), ContractError> {
+    if *sender == state.owner {
+        return Ok(());
+    }
+    match OPERATORS.may_load(deps.storage, (&state.owner, sender))? {
+        Some(expires) if !expires.is_expired(block) => Ok(()),
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
+
+/// Owner, an operator or an approved spender
+fn check_can_send(
+    deps: Deps,
+    block: &BlockInfo,
+    sender: &Addr,
+    state: &State,
+) -> Result<(), ContractError> {
+    let approved = state
+        .approvals
+        .iter()
+        .any(|a| a.spender == *sender && !a.is_expired(block));
+    if approved {
+        return Ok(());
     }
+    check_can_approve(deps, block, sender, state)
+}
+
+/// Moves the option to recipient, approvals of the previous owner are dropped
+fn transfer_option(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    recipient: &str,
+    token_id: &str,
+) -> Result<u64, ContractError> {
+    let id = parse_token_id(token_id)?;
+    let mut state = options().load(deps.storage, id)?;
+    check_can_send(deps.as_ref(), &env.block, &info.sender, &state)?;
+
+    // set new owner on state
+    state.owner = deps.api.addr_validate(recipient)?;
+    state.approvals = vec![];
+    state.price = None;
+    options().save(deps.storage, id, &state)?;
+    Ok(id)
 }
 
 pub fn handle_transfer(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     recipient: String,
+    token_id: String,
 ) -> Result<Response, ContractError> {
-    let state_loaded = STATE.load(deps.storage)?;
+    let id = transfer_option(deps, &env, &info, &recipient, &token_id)?;
 
-    if info.sender != state_loaded.owner {
-        return Err(ContractError::Unauthorized {});
+    Ok(Response::new()
+        .add_attribute("method", "handle_transfer")
+        .add_attribute("id", id.to_string()))
+}
+
+pub fn handle_send(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: String,
+    token_id: String,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let id = transfer_option(deps, &env, &info, &contract, &token_id)?;
+
+    let receive = Cw721ReceiveMsg {
+        sender: info.sender.to_string(),
+        token_id,
+        msg,
+    };
+    Ok(Response::new()
+        .add_message(receive.into_cosmos_msg(contract)?)
+        .add_attribute("method", "handle_send")
+        .add_attribute("id", id.to_string()))
+}
+
+pub fn handle_approve(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    token_id: String,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let id = parse_token_id(&token_id)?;
+    let mut state = options().load(deps.storage, id)?;
+    check_can_approve(deps.as_ref(), &env.block, &info.sender, &state)?;
+
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::ApprovalExpired {});
     }
+    let spender_addr = deps.api.addr_validate(&spender)?;
 
-    let recipient_address = deps.api.addr_validate(&recipient)?;
+    // replace any earlier approval of the spender
+    state.approvals.retain(|a| a.spender != spender_addr);
+    state.approvals.push(Approval {
+        spender: spender_addr,
+        expires,
+    });
+    options().save(deps.storage, id, &state)?;
 
-    // set new owner on state
-    STATE.update(deps.storage, |mut state| -> Result<_, ContractError> {
-        state.owner = recipient_address;
-        Ok(state)
-    })?;
+    Ok(Response::new()
+        .add_attribute("method", "handle_approve")
+        .add_attribute("id", id.to_string())
+        .add_attribute("spender", spender))
+}
+
+pub fn handle_revoke(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let id = parse_token_id(&token_id)?;
+    let mut state = options().load(deps.storage, id)?;
+    check_can_approve(deps.as_ref(), &env.block, &info.sender, &state)?;
+
+    let spender_addr = deps.api.addr_validate(&spender)?;
+    state.approvals.retain(|a| a.spender != spender_addr);
+    options().save(deps.storage, id, &state)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "handle_revoke")
+        .add_attribute("id", id.to_string())
+        .add_attribute("spender", spender))
+}
+
+pub fn handle_approve_all(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operator: String,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::ApprovalExpired {});
+    }
+    let operator_addr = deps.api.addr_validate(&operator)?;
+    OPERATORS.save(deps.storage, (&info.sender, &operator_addr), &expires)?;
 
-    Ok(Response::new().add_attribute("method", "handle_transfer"))
+    Ok(Response::new()
+        .add_attribute("method", "handle_approve_all")
+        .add_attribute("operator", operator))
+}
+
+pub fn handle_revoke_all(
+    deps: DepsMut,
+    info: MessageInfo,
+    operator: String,
+) -> Result<Response, ContractError> {
+    let operator_addr = deps.api.addr_validate(&operator)?;
+    OPERATORS.remove(deps.storage, (&info.sender, &operator_addr));
+
+    Ok(Response::new()
+        .add_attribute("method", "handle_revoke_all")
+        .add_attribute("operator", operator))
 }
 
 pub fn handle_execute(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    id: u64,
 ) -> Result<Response, ContractError> {
-    let state = STATE.load(deps.storage)?;
+    let sent = normalize_balance(Balance::from(info.funds));
+    execute_option(deps, env, info.sender, sent, id)
+}
 
-    if info.sender != state.owner {
+fn execute_option(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    sent: Balance,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut state = options().load(deps.storage, id)?;
+
+    if sender != state.owner {
         return Err(ContractError::Unauthorized {});
     }
 
     // ensure not expired
-    if env.block.height >= state.expires {
+    if state.expires.is_expired(&env.block) {
         return Err(ContractError::Expired {});
     }
 
-    // ensure sending proper counter_offer
-    if info.funds != state.counter_offer {
-        return Err(ContractError::NotEqualCounterOffer {
-            val: state.counter_offer,
-        });
+    // ensure sending the counter_offer or a proportional share of it
+    state.counter_offer = normalize_balance(state.counter_offer);
+    let share = if sent == state.counter_offer {
+        None
+    } else {
+        match exercised_share(&sent, &state.counter_offer) {
+            Some((paid, total)) if paid < total => Some((paid, total)),
+            Some(_) => None,
+            None => {
+                return Err(ContractError::NotEqualCounterOffer {
+                    expected: state.counter_offer,
+                    received: sent,
+                })
+            }
+        }
+    };
+
+    let res = Response::new()
+        .add_attribute("method", "handle_execute")
+        .add_attribute("id", id.to_string())
+        .add_attribute("kind", state.kind.as_str());
+
+    let (paid, total) = match share {
+        Some(share) => share,
+        None => {
+            // delete the option
+            remove_option(deps.storage, id)?;
+
+            let msgs = settle(&state, state.collateral.clone(), sent)?;
+            return Ok(res.add_messages(msgs));
+        }
+    };
+
+    // rounding down keeps the remaining collateral covering the remaining counter_offer,
+    // the last exercise releases whatever is left
+    let released = scale_balance(&state.collateral, paid, total);
+    if released.is_empty() {
+        return Err(ContractError::NothingReleased {});
     }
+    state.collateral = subtract_balance(state.collateral, &released)?;
+    state.counter_offer = subtract_balance(state.counter_offer, &sent)?;
+    // the listed price was asked for the whole option
+    state.price = None;
+    options().save(deps.storage, id, &state)?;
 
-    // delete the option
-    STATE.remove(deps.storage);
+    Ok(res
+        .add_messages(settle(&state, released, sent)?)
+        .add_attribute("exercised", format!("{}/{}", paid, total)))
+}
 
-    let res = Response::new()
-        .add_message(BankMsg::Send {
-            to_address: state.creator.to_string(),
-            amount: state.counter_offer,
-        })
-        .add_message(BankMsg::Send {
-            to_address: state.owner.to_string(),
-            amount: state.collateral,
-        })
-        .add_attribute("method", "handle_execute");
-    Ok(res)
+/// Swaps released collateral for the sent counter_offer: the owner of a call buys the
+/// underlying from the creator at the strike, the owner of a put sells it to them
+fn settle(state: &State, released: Balance, sent: Balance) -> StdResult<Vec<CosmosMsg>> {
+    let (underlying, strike) = state.kind.orient(released, sent);
+    let (buyer, seller) = match state.kind {
+        OptionKind::Call => (&state.owner, &state.creator),
+        OptionKind::Put => (&state.creator, &state.owner),
+    };
+    let mut msgs = transfer_msgs(seller, strike)?;
+    msgs.extend(transfer_msgs(buyer, underlying)?);
+    Ok(msgs)
 }
 
-pub fn handle_burn(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
-    let state = STATE.load(deps.storage)?;
+pub fn handle_burn(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let state = options().load(deps.storage, id)?;
 
     // ensure is expired
-    if env.block.height < state.expires {
+    if !state.expires.is_expired(&env.block) {
         return Err(ContractError::NotExpired {});
     }
 
@@ -129,96 +673,550 @@ pub fn handle_burn(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Respons
     }
 
     // delete the option
-    STATE.remove(deps.storage);
+    remove_option(deps.storage, id)?;
 
     Ok(Response::new()
-        .add_message(BankMsg::Send {
-            to_address: state.creator.to_string(),
-            amount: state.collateral,
-        })
-        .add_attribute("method", "handle_burn"))
+        .add_messages(transfer_msgs(&state.creator, state.collateral)?)
+        .add_attribute("method", "handle_burn")
+        .add_attribute("id", id.to_string())
+        .add_attribute("returned", state.kind.locked()))
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+pub fn handle_cancel(deps: DepsMut, info: MessageInfo, id: u64) -> Result<Response, ContractError> {
+    let state = options().load(deps.storage, id)?;
+
+    // nobody else holds a right to the collateral yet
+    if info.sender != state.creator || state.owner != state.creator {
+        return Err(ContractError::Unauthorized {});
     }
+
+    // delete the option
+    remove_option(deps.storage, id)?;
+
+    Ok(Response::new()
+        .add_messages(transfer_msgs(&state.creator, state.collateral)?)
+        .add_attribute("method", "handle_cancel")
+        .add_attribute("id", id.to_string())
+        .add_attribute("returned", state.kind.locked()))
 }
 
-fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
-    let state = STATE.load(deps.storage)?;
-    Ok(state)
+pub fn handle_add_collateral(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let added = normalize_balance(Balance::from(info.funds));
+    add_collateral(deps, env, info.sender, added, id)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use cosmwasm_std::testing::{
-        mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info,
-    };
-    use cosmwasm_std::{attr, coins, from_binary, CosmosMsg, ReplyOn, SubMsg};
+fn add_collateral(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    added: Balance,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut state = options().load(deps.storage, id)?;
+    if sender != state.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+    if state.expires.is_expired(&env.block) {
+        return Err(ContractError::Expired {});
+    }
+    if added.is_empty() {
+        return Err(ContractError::InvalidCollateral {});
+    }
 
-    #[test]
-    fn proper_initialization() {
-        let mut deps = mock_dependencies();
-        let msg = InstantiateMsg {
-            counter_offer: coins(40, "ETH"),
-            expires: 100_000,
-        };
-        let info = mock_info("creator", &coins(1, "BTC"));
+    state.collateral = add_balance(state.collateral, added.clone())?;
+    options().save(deps.storage, id, &state)?;
 
-        // we can just call .unwrap() to assert this was a success
-        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
+    Ok(Response::new()
+        .add_attribute("method", "handle_add_collateral")
+        .add_attribute("id", id.to_string())
+        .add_attribute("added", describe_balance(&added))
+        .add_attribute("collateral", describe_balance(&state.collateral)))
+}
 
-        // it worked, let's query the state
-        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
-        let value: ConfigResponse = from_binary(&res).unwrap();
-        assert_eq!(100_000, value.expires);
-        assert_eq!("creator", value.owner);
-        assert_eq!("creator", value.creator);
-        assert_eq!(coins(1, "BTC"), value.collateral);
-        assert_eq!(coins(40, "ETH"), value.counter_offer);
+/// The owner never has to consent, the new counter_offer can only ask less
+pub fn handle_update_counter_offer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    counter_offer: Balance,
+) -> Result<Response, ContractError> {
+    let mut state = options().load(deps.storage, id)?;
+    if info.sender != state.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+    if state.expires.is_expired(&env.block) {
+        return Err(ContractError::Expired {});
     }
 
-    #[test]
-    fn transfer() {
-        let mut deps = mock_dependencies();
-
-        let msg = InstantiateMsg {
-            counter_offer: coins(40, "ETH"),
-            expires: 100_000,
-        };
-        let info = mock_info("creator", &coins(1, "BTC"));
-        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
+    let counter_offer = validate_balance(deps.api, counter_offer)?;
+    if !is_lower_balance(&counter_offer, &state.counter_offer) {
+        return Err(ContractError::CounterOfferNotLower {});
+    }
 
-        // random cannot transfer
-        let info = mock_info("anyone", &[]);
-        let msg = ExecuteMsg::Transfer {
-            recipient: "Someone".to_string(),
-        };
-        let res = execute(deps.as_mut(), mock_env(), info, msg);
+    let previous = std::mem::replace(&mut state.counter_offer, counter_offer);
+    options().save(deps.storage, id, &state)?;
 
-        match res.unwrap_err() {
-            ContractError::Unauthorized {} => {}
-            e => panic!("Unexpected error: {:?}", e),
-        }
+    Ok(Response::new()
+        .add_attribute("method", "handle_update_counter_offer")
+        .add_attribute("id", id.to_string())
+        .add_attribute("previous", describe_balance(&previous))
+        .add_attribute("counter_offer", describe_balance(&state.counter_offer)))
+}
 
-        // owner can transfer
-        let info = mock_info("creator", &[]);
-        let msg = ExecuteMsg::Transfer {
-            recipient: "Someone".to_string(),
-        };
-        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(res.attributes.len(), 1);
-        assert_eq!(res.attributes[0], attr("method", "handle_transfer"));
+pub fn handle_list_for_sale(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    price: Vec<Coin>,
+) -> Result<Response, ContractError> {
+    let mut state = options().load(deps.storage, id)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if state.expires.is_expired(&env.block) {
+        return Err(ContractError::Expired {});
+    }
+    let price = normalize_coins(price);
+    if price.is_empty() {
+        return Err(ContractError::InvalidPrice {});
+    }
+
+    state.price = Some(price);
+    options().save(deps.storage, id, &state)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "handle_list_for_sale")
+        .add_attribute("id", id.to_string()))
+}
+
+pub fn handle_cancel_listing(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut state = options().load(deps.storage, id)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if state.price.is_none() {
+        return Err(ContractError::NotListed {});
+    }
+
+    state.price = None;
+    options().save(deps.storage, id, &state)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "handle_cancel_listing")
+        .add_attribute("id", id.to_string()))
+}
+
+pub fn handle_buy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut state = options().load(deps.storage, id)?;
+    let price = state.price.take().ok_or(ContractError::NotListed {})?;
+    if state.expires.is_expired(&env.block) {
+        return Err(ContractError::Expired {});
+    }
+    let sent = normalize_coins(info.funds);
+    if sent != price {
+        return Err(ContractError::IncorrectPremium {
+            expected: price,
+            received: sent,
+        });
+    }
+
+    // the buyer becomes owner in the same transaction the seller is paid
+    let seller = std::mem::replace(&mut state.owner, info.sender.clone());
+    state.approvals = vec![];
+    options().save(deps.storage, id, &state)?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: seller.to_string(),
+            amount: price,
+        })
+        .add_attribute("method", "handle_buy")
+        .add_attribute("id", id.to_string())
+        .add_attribute("seller", seller)
+        .add_attribute("buyer", info.sender))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Option { id } => to_binary(&query_option(deps, id)?),
+        QueryMsg::OwnerOf {
+            token_id,
+            include_expired,
+        } => to_binary(&query_owner_of(
+            deps,
+            env,
+            token_id,
+            include_expired.unwrap_or(false),
+        )?),
+        QueryMsg::Approval {
+            token_id,
+            spender,
+            include_expired,
+        } => to_binary(&query_approval(
+            deps,
+            env,
+            token_id,
+            spender,
+            include_expired.unwrap_or(false),
+        )?),
+        QueryMsg::Approvals {
+            token_id,
+            include_expired,
+        } => to_binary(&query_approvals(
+            deps,
+            env,
+            token_id,
+            include_expired.unwrap_or(false),
+        )?),
+        QueryMsg::AllOperators {
+            owner,
+            include_expired,
+            start_after,
+            limit,
+        } => to_binary(&query_all_operators(
+            deps,
+            env,
+            owner,
+            include_expired.unwrap_or(false),
+            start_after,
+            limit,
+        )?),
+        QueryMsg::NumTokens {} => to_binary(&query_num_tokens(deps)?),
+        QueryMsg::ContractInfo {} => to_binary(&CONTRACT_INFO.load(deps.storage)?),
+        QueryMsg::NftInfo { token_id } => to_binary(&query_nft_info(deps, token_id)?),
+        QueryMsg::AllNftInfo {
+            token_id,
+            include_expired,
+        } => to_binary(&query_all_nft_info(
+            deps,
+            env,
+            token_id,
+            include_expired.unwrap_or(false),
+        )?),
+        QueryMsg::Tokens {
+            owner,
+            start_after,
+            limit,
+        } => to_binary(&query_tokens(deps, owner, start_after, limit)?),
+        QueryMsg::AllTokens { start_after, limit } => {
+            to_binary(&query_all_tokens(deps, start_after, limit)?)
+        }
+    }
+}
+
+fn query_option(deps: Deps, id: u64) -> StdResult<OptionResponse> {
+    options().load(deps.storage, id)
+}
+
+fn humanize_approvals(
+    block: &BlockInfo,
+    state: &State,
+    include_expired: bool,
+) -> Vec<cw721::Approval> {
+    state
+        .approvals
+        .iter()
+        .filter(|a| include_expired || !a.is_expired(block))
+        .map(|a| cw721::Approval {
+            spender: a.spender.to_string(),
+            expires: a.expires,
+        })
+        .collect()
+}
+
+fn query_owner_of(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    include_expired: bool,
+) -> StdResult<OwnerOfResponse> {
+    let state = options().load(deps.storage, parse_token_id(&token_id)?)?;
+    Ok(OwnerOfResponse {
+        owner: state.owner.to_string(),
+        approvals: humanize_approvals(&env.block, &state, include_expired),
+    })
+}
+
+fn query_approval(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    spender: String,
+    include_expired: bool,
+) -> StdResult<ApprovalResponse> {
+    let state = options().load(deps.storage, parse_token_id(&token_id)?)?;
+
+    // the owner can always transfer
+    if state.owner == spender {
+        return Ok(ApprovalResponse {
+            approval: cw721::Approval {
+                spender,
+                expires: Expiration::Never {},
+            },
+        });
+    }
+
+    humanize_approvals(&env.block, &state, include_expired)
+        .into_iter()
+        .find(|a| a.spender == spender)
+        .map(|approval| ApprovalResponse { approval })
+        .ok_or_else(|| StdError::not_found("Approval not found"))
+}
+
+fn query_approvals(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    include_expired: bool,
+) -> StdResult<ApprovalsResponse> {
+    let state = options().load(deps.storage, parse_token_id(&token_id)?)?;
+    Ok(ApprovalsResponse {
+        approvals: humanize_approvals(&env.block, &state, include_expired),
+    })
+}
+
+fn query_all_operators(
+    deps: Deps,
+    env: Env,
+    owner: String,
+    include_expired: bool,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<OperatorsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_addr = maybe_addr(deps.api, start_after)?;
+    let start = start_addr.as_ref().map(Bound::exclusive);
+
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let operators = OPERATORS
+        .prefix(&owner_addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, expires)) => include_expired || !expires.is_expired(&env.block),
+            Err(_) => true,
+        })
+        .take(limit)
+        .map(|item| {
+            item.map(|(spender, expires)| cw721::Approval {
+                spender: spender.to_string(),
+                expires,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(OperatorsResponse { operators })
+}
+
+fn query_num_tokens(deps: Deps) -> StdResult<NumTokensResponse> {
+    let count = NUM_TOKENS.may_load(deps.storage)?.unwrap_or_default();
+    Ok(NumTokensResponse { count })
+}
+
+fn query_nft_info(deps: Deps, token_id: String) -> StdResult<OptionNftInfoResponse> {
+    let state = options().load(deps.storage, parse_token_id(&token_id)?)?;
+    Ok(OptionNftInfoResponse {
+        token_uri: None,
+        extension: OptionExtension {
+            underlying: state.underlying().clone(),
+            strike: state.strike().clone(),
+            creator: state.creator,
+            kind: state.kind,
+            collateral: state.collateral,
+            counter_offer: state.counter_offer,
+            expires: state.expires,
+        },
+    })
+}
+
+fn query_all_nft_info(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    include_expired: bool,
+) -> StdResult<AllNftInfoResponse<OptionExtension>> {
+    Ok(AllNftInfoResponse {
+        access: query_owner_of(deps, env, token_id.clone(), include_expired)?,
+        info: query_nft_info(deps, token_id)?,
+    })
+}
+
+fn query_tokens(
+    deps: Deps,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|token_id| parse_token_id(&token_id))
+        .transpose()?
+        .map(Bound::exclusive);
+
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let tokens = options()
+        .idx
+        .owner
+        .prefix(owner_addr)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|id| id.to_string()))
+        .collect::<StdResult<_>>()?;
+    Ok(TokensResponse { tokens })
+}
+
+fn query_all_tokens(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|token_id| parse_token_id(&token_id))
+        .transpose()?
+        .map(Bound::exclusive);
+
+    let tokens = options()
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|id| id.to_string()))
+        .collect::<StdResult<_>>()?;
+    Ok(TokensResponse { tokens })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg::CreateOptionMsg;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{attr, coin, coins, from_binary, ReplyOn, SubMsg};
+
+    #[test]
+    fn proper_initialization() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            option: Some(CreateOptionMsg {
+                underlying: Balance::from(coins(1, "BTC")),
+                strike: Balance::from(coins(40, "ETH")),
+                expires: Expiration::AtHeight(100_000),
+                kind: None,
+            }),
+            name: None,
+            symbol: None,
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+
+        // we can just call .unwrap() to assert this was a success
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // it worked, let's query the state
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Option { id: 1 }).unwrap();
+        let value: OptionResponse = from_binary(&res).unwrap();
+        assert_eq!(Expiration::AtHeight(100_000), value.expires);
+        assert_eq!("creator", value.owner);
+        assert_eq!("creator", value.creator);
+        assert_eq!(Balance::from(coins(1, "BTC")), value.collateral);
+        assert_eq!(Balance::from(coins(40, "ETH")), value.counter_offer);
+    }
+
+    #[test]
+    fn empty_initialization() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            option: None,
+            name: None,
+            symbol: None,
+        };
+
+        // funds without terms are rejected
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        match err {
+            ContractError::Payment(_) => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let info = mock_info("creator", &[]);
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.attributes, vec![attr("method", "instantiate")]);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::NumTokens {}).unwrap();
+        let value: NumTokensResponse = from_binary(&res).unwrap();
+        assert_eq!(0, value.count);
+
+        // the first option written afterwards gets id 1
+        let msg = ExecuteMsg::CreateOption(CreateOptionMsg {
+            underlying: Balance::from(coins(1, "BTC")),
+            strike: Balance::from(coins(40, "ETH")),
+            expires: Expiration::AtHeight(100_000),
+            kind: None,
+        });
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.attributes[1], attr("id", "1"));
+    }
+
+    #[test]
+    fn transfer() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            option: Some(CreateOptionMsg {
+                underlying: Balance::from(coins(1, "BTC")),
+                strike: Balance::from(coins(40, "ETH")),
+                expires: Expiration::AtHeight(100_000),
+                kind: None,
+            }),
+            name: None,
+            symbol: None,
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // random cannot transfer
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::TransferNft {
+            recipient: "Someone".to_string(),
+            token_id: "1".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+
+        match res.unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("Unexpected error: {:?}", e),
+        }
+
+        // owner can transfer
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::TransferNft {
+            recipient: "Someone".to_string(),
+            token_id: "1".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.attributes.len(), 2);
+        assert_eq!(res.attributes[0], attr("method", "handle_transfer"));
 
         // check updated properly
-        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
-        let value: ConfigResponse = from_binary(&res).unwrap();
-        assert_eq!(100_000, value.expires);
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Option { id: 1 }).unwrap();
+        let value: OptionResponse = from_binary(&res).unwrap();
+        assert_eq!(Expiration::AtHeight(100_000), value.expires);
         assert_eq!("Someone", value.owner);
         assert_eq!("creator", value.creator);
     }
@@ -229,8 +1227,14 @@ mod tests {
 
         let counter_offer = coins(40, "ETH");
         let msg = InstantiateMsg {
-            counter_offer: counter_offer.clone(),
-            expires: 100_000,
+            option: Some(CreateOptionMsg {
+                underlying: Balance::from(coins(1, "BTC")),
+                strike: Balance::from(counter_offer.clone()),
+                expires: Expiration::AtHeight(100_000),
+                kind: None,
+            }),
+            name: None,
+            symbol: None,
         };
         let info = mock_info("creator", &coins(1, "BTC"));
         let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -238,7 +1242,7 @@ mod tests {
 
         // random cannot execute
         let info = mock_info("anyone", &counter_offer);
-        let msg = ExecuteMsg::Execute {};
+        let msg = ExecuteMsg::Execute { id: 1 };
         let res = execute(deps.as_mut(), mock_env(), info, msg);
 
         match res.unwrap_err() {
@@ -247,10 +1251,10 @@ mod tests {
         }
 
         // expired cannot execute
-        let mut info = mock_info("creator", &counter_offer);
+        let info = mock_info("creator", &counter_offer);
         let mut env = mock_env();
         env.block.height = 200_000;
-        let msg = ExecuteMsg::Execute {};
+        let msg = ExecuteMsg::Execute { id: 1 };
         let res = execute(deps.as_mut(), env, info, msg.clone());
 
         match res.unwrap_err() {
@@ -259,19 +1263,20 @@ mod tests {
         }
 
         // bad counter_offer cannot execute
-        let msg_offer = coins(39, "ETH");
+        let msg_offer = coins(41, "ETH");
         let info = mock_info("creator", &msg_offer);
         let res = execute(deps.as_mut(), mock_env(), info, msg);
         match res.unwrap_err() {
-            ContractError::NotEqualCounterOffer { val } => {
-                assert_eq!(val, counter_offer)
+            ContractError::NotEqualCounterOffer { expected, received } => {
+                assert_eq!(expected, Balance::from(counter_offer.clone()));
+                assert_eq!(received, Balance::from(msg_offer.clone()));
             }
             e => panic!("unexpected error: {}", e),
         }
 
         // proper execution
         let info = mock_info("creator", &counter_offer);
-        let msg = ExecuteMsg::Execute {};
+        let msg = ExecuteMsg::Execute { id: 1 };
         let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
         assert_eq!(res.messages.len(), 2);
         assert_eq!(
@@ -302,7 +1307,7 @@ mod tests {
         );
 
         // check updated properly
-        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap_err();
+        query(deps.as_ref(), mock_env(), QueryMsg::Option { id: 1 }).unwrap_err();
     }
 
     #[test]
@@ -311,10 +1316,16 @@ mod tests {
 
         let counter_offer = coins(40, "ETH");
         let collateral = coins(1, "BTC");
-        let msg_expires = 100_000;
+        let msg_expires = Expiration::AtHeight(100_000);
         let msg = InstantiateMsg {
-            counter_offer: counter_offer.clone(),
-            expires: msg_expires,
+            option: Some(CreateOptionMsg {
+                underlying: Balance::from(collateral.clone()),
+                strike: Balance::from(counter_offer.clone()),
+                expires: msg_expires,
+                kind: None,
+            }),
+            name: None,
+            symbol: None,
         };
         let info = mock_info("creator", &collateral);
 
@@ -323,14 +1334,15 @@ mod tests {
 
         // set new owner
         let info = mock_info("creator", &[]);
-        let msg = ExecuteMsg::Transfer {
+        let msg = ExecuteMsg::TransferNft {
             recipient: "owner".to_string(),
+            token_id: "1".to_string(),
         };
         let _ = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
         // non-expired cannot execute
         let info = mock_info("anyone", &[]);
-        let msg = ExecuteMsg::Burn {};
+        let msg = ExecuteMsg::Burn { id: 1 };
         let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
         match err {
             ContractError::NotExpired {} => {}
@@ -341,7 +1353,7 @@ mod tests {
         let info = mock_info("anyone", &counter_offer);
         let mut env = mock_env();
         env.block.height = 200_000;
-        let msg = ExecuteMsg::Burn {};
+        let msg = ExecuteMsg::Burn { id: 1 };
         let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
         match err {
             ContractError::BurnFunds {} => {}
@@ -352,7 +1364,7 @@ mod tests {
         let info = mock_info("anyone", &[]);
         let mut env = mock_env();
         env.block.height = 200_000;
-        let msg = ExecuteMsg::Burn {};
+        let msg = ExecuteMsg::Burn { id: 1 };
         let res = execute(deps.as_mut(), env, info, msg).unwrap();
         assert_eq!(res.messages.len(), 1);
         assert_eq!(
@@ -370,6 +1382,1121 @@ mod tests {
         );
 
         // check deleted
-        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap_err();
+        query(deps.as_ref(), mock_env(), QueryMsg::Option { id: 1 }).unwrap_err();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::NumTokens {}).unwrap();
+        let value: NumTokensResponse = from_binary(&res).unwrap();
+        assert_eq!(value.count, 0);
+    }
+
+    #[test]
+    fn create_option() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            option: Some(CreateOptionMsg {
+                underlying: Balance::from(coins(1, "BTC")),
+                strike: Balance::from(coins(40, "ETH")),
+                expires: Expiration::AtHeight(100_000),
+                kind: None,
+            }),
+            name: None,
+            symbol: None,
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // expired cannot be created
+        let info = mock_info("writer", &coins(2, "BTC"));
+        let msg = ExecuteMsg::CreateOption(CreateOptionMsg {
+            underlying: Balance::from(coins(2, "BTC")),
+            strike: Balance::from(coins(80, "ETH")),
+            expires: Expiration::AtHeight(1),
+            kind: None,
+        });
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        match err {
+            ContractError::CreateExpired {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let msg = ExecuteMsg::CreateOption(CreateOptionMsg {
+            underlying: Balance::from(coins(2, "BTC")),
+            strike: Balance::from(coins(80, "ETH")),
+            expires: Expiration::AtHeight(100_000),
+            kind: None,
+        });
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.attributes[1], attr("id", "2"));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Option { id: 2 }).unwrap();
+        let value: OptionResponse = from_binary(&res).unwrap();
+        assert_eq!("writer", value.owner);
+        assert_eq!(Balance::from(coins(2, "BTC")), value.collateral);
+        assert_eq!(Balance::from(coins(80, "ETH")), value.counter_offer);
+
+        // options are executed independently
+        let info = mock_info("writer", &coins(80, "ETH"));
+        let msg = ExecuteMsg::Execute { id: 2 };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.messages.len(), 2);
+
+        query(deps.as_ref(), mock_env(), QueryMsg::Option { id: 2 }).unwrap_err();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Option { id: 1 }).unwrap();
+        let value: OptionResponse = from_binary(&res).unwrap();
+        assert_eq!("creator", value.owner);
+    }
+
+    #[test]
+    fn cw721_interface() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            option: Some(CreateOptionMsg {
+                underlying: Balance::from(coins(1, "BTC")),
+                strike: Balance::from(coins(40, "ETH")),
+                expires: Expiration::AtHeight(100_000),
+                kind: None,
+            }),
+            name: None,
+            symbol: None,
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let msg = ExecuteMsg::CreateOption(CreateOptionMsg {
+            underlying: Balance::from(coins(2, "BTC")),
+            strike: Balance::from(coins(80, "ETH")),
+            expires: Expiration::AtHeight(100_000),
+            kind: None,
+        });
+        let info = mock_info("creator", &coins(2, "BTC"));
+        let _ = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // spender cannot transfer before approval
+        let transfer = ExecuteMsg::TransferNft {
+            recipient: "buyer".to_string(),
+            token_id: "1".to_string(),
+        };
+        let info = mock_info("spender", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), transfer.clone()).unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let msg = ExecuteMsg::Approve {
+            spender: "spender".to_string(),
+            token_id: "1".to_string(),
+            expires: None,
+        };
+        let _ = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = QueryMsg::Approval {
+            token_id: "1".to_string(),
+            spender: "spender".to_string(),
+            include_expired: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: ApprovalResponse = from_binary(&res).unwrap();
+        assert_eq!(value.approval.expires, Expiration::Never {});
+
+        // approved spender transfers, approvals are cleared
+        let _ = execute(deps.as_mut(), mock_env(), info, transfer).unwrap();
+        let msg = QueryMsg::OwnerOf {
+            token_id: "1".to_string(),
+            include_expired: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: OwnerOfResponse = from_binary(&res).unwrap();
+        assert_eq!(value.owner, "buyer");
+        assert!(value.approvals.is_empty());
+
+        // operators can send every option of the owner
+        let msg = ExecuteMsg::ApproveAll {
+            operator: "operator".to_string(),
+            expires: None,
+        };
+        let _ = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::SendNft {
+            contract: "market".to_string(),
+            token_id: "2".to_string(),
+            msg: Binary::from(b"list".to_vec()),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("operator", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            Cw721ReceiveMsg {
+                sender: "operator".to_string(),
+                token_id: "2".to_string(),
+                msg: Binary::from(b"list".to_vec()),
+            }
+            .into_cosmos_msg("market")
+            .unwrap()
+        );
+
+        let msg = QueryMsg::Tokens {
+            owner: "market".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: TokensResponse = from_binary(&res).unwrap();
+        assert_eq!(value.tokens, vec!["2".to_string()]);
+
+        // the previous owner no longer lists transferred options
+        let msg = QueryMsg::Tokens {
+            owner: "creator".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: TokensResponse = from_binary(&res).unwrap();
+        assert!(value.tokens.is_empty());
+        let msg = QueryMsg::Tokens {
+            owner: "buyer".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: TokensResponse = from_binary(&res).unwrap();
+        assert_eq!(value.tokens, vec!["1".to_string()]);
+        let msg = QueryMsg::Tokens {
+            owner: "buyer".to_string(),
+            start_after: Some("1".to_string()),
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: TokensResponse = from_binary(&res).unwrap();
+        assert!(value.tokens.is_empty());
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::NumTokens {}).unwrap();
+        let value: NumTokensResponse = from_binary(&res).unwrap();
+        assert_eq!(value.count, 2);
+
+        let msg = QueryMsg::NftInfo {
+            token_id: "2".to_string(),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: OptionNftInfoResponse = from_binary(&res).unwrap();
+        assert_eq!(value.extension.collateral, Balance::from(coins(2, "BTC")));
+        assert_eq!(
+            value.extension.counter_offer,
+            Balance::from(coins(80, "ETH"))
+        );
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ContractInfo {}).unwrap();
+        let value: ContractInfoResponse = from_binary(&res).unwrap();
+        assert_eq!(value.symbol, "OPTION");
+    }
+
+    #[test]
+    fn time_expiration() {
+        let mut deps = mock_dependencies();
+
+        // options must expire eventually
+        let msg = InstantiateMsg {
+            option: Some(CreateOptionMsg {
+                underlying: Balance::from(coins(1, "BTC")),
+                strike: Balance::from(coins(40, "ETH")),
+                expires: Expiration::Never {},
+                kind: None,
+            }),
+            name: None,
+            symbol: None,
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        match err {
+            ContractError::NeverExpires {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let expires = mock_env().block.time.plus_seconds(3600);
+        let msg = InstantiateMsg {
+            option: Some(CreateOptionMsg {
+                underlying: Balance::from(coins(1, "BTC")),
+                strike: Balance::from(coins(40, "ETH")),
+                expires: Expiration::AtTime(expires),
+                kind: None,
+            }),
+            name: None,
+            symbol: None,
+        };
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // not expired before the time, whatever the height
+        let mut env = mock_env();
+        env.block.height = 200_000;
+        let info = mock_info("anyone", &[]);
+        let err = execute(deps.as_mut(), env, info, ExecuteMsg::Burn { id: 1 }).unwrap_err();
+        match err {
+            ContractError::NotExpired {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let mut env = mock_env();
+        env.block.time = expires;
+        let info = mock_info("creator", &coins(40, "ETH"));
+        let msg = ExecuteMsg::Execute { id: 1 };
+        let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        match err {
+            ContractError::Expired {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let info = mock_info("anyone", &[]);
+        let res = execute(deps.as_mut(), env, info, ExecuteMsg::Burn { id: 1 }).unwrap();
+        assert_eq!(res.messages.len(), 1);
+    }
+
+    #[test]
+    fn cw20_collateral_and_counter_offer() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            option: Some(CreateOptionMsg {
+                underlying: Balance::from(coins(1, "BTC")),
+                strike: Balance::from(coins(40, "ETH")),
+                expires: Expiration::AtHeight(100_000),
+                kind: None,
+            }),
+            name: None,
+            symbol: None,
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // option 2 locks governance tokens and asks for stable tokens
+        let usd = Cw20CoinVerified {
+            address: Addr::unchecked("usd"),
+            amount: Uint128::new(500),
+        };
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "writer".to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::CreateOption(CreateOptionMsg {
+                underlying: Balance::Cw20(Cw20CoinVerified {
+                    address: Addr::unchecked("gov"),
+                    amount: Uint128::new(100),
+                }),
+                strike: Balance::Cw20(usd.clone()),
+                expires: Expiration::AtHeight(100_000),
+                kind: None,
+            }))
+            .unwrap(),
+        });
+        let _ = execute(deps.as_mut(), mock_env(), mock_info("gov", &[]), msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Option { id: 2 }).unwrap();
+        let value: OptionResponse = from_binary(&res).unwrap();
+        assert_eq!("writer", value.owner);
+        assert_eq!(
+            Balance::Cw20(Cw20CoinVerified {
+                address: Addr::unchecked("gov"),
+                amount: Uint128::new(100),
+            }),
+            value.collateral
+        );
+
+        // native funds or other tokens do not match the counter_offer
+        let info = mock_info("writer", &coins(500, "usd"));
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Execute { id: 2 },
+        )
+        .unwrap_err();
+        match err {
+            ContractError::NotEqualCounterOffer { expected, .. } => {
+                assert_eq!(expected, Balance::Cw20(usd))
+            }
+            e => panic!("unexpected error: {}", e),
+        }
+        let exercise = |amount: u128| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "writer".to_string(),
+                amount: Uint128::new(amount),
+                msg: to_binary(&ReceiveMsg::Execute { id: 2 }).unwrap(),
+            })
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gov", &[]),
+            exercise(500),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::NotEqualCounterOffer { .. } => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("usd", &[]),
+            exercise(500),
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "usd".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "writer".to_string(),
+                    amount: Uint128::new(500),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "gov".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "writer".to_string(),
+                    amount: Uint128::new(100),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn put_option() {
+        let mut deps = mock_dependencies();
+
+        // both options are written on 1 BTC at a strike of 40 ETH
+        let underlying = Balance::from(coins(1, "BTC"));
+        let strike = Balance::from(coins(40, "ETH"));
+        let msg = InstantiateMsg {
+            option: Some(CreateOptionMsg {
+                underlying: underlying.clone(),
+                strike: strike.clone(),
+                expires: Expiration::AtHeight(100_000),
+                kind: None,
+            }),
+            name: None,
+            symbol: None,
+        };
+        let info = mock_info("writer", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // put writer has to lock the strike, not the underlying
+        let put = CreateOptionMsg {
+            underlying: underlying.clone(),
+            strike: strike.clone(),
+            expires: Expiration::AtHeight(100_000),
+            kind: Some(OptionKind::Put),
+        };
+        let info = mock_info("writer", &coins(1, "BTC"));
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::CreateOption(put.clone()),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::WrongCollateral { side, .. } => assert_eq!("strike", side),
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let info = mock_info("writer", &coins(40, "ETH"));
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::CreateOption(put),
+        )
+        .unwrap();
+        assert_eq!(res.attributes[2], attr("kind", "put"));
+
+        let msg = QueryMsg::NftInfo {
+            token_id: "2".to_string(),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: OptionNftInfoResponse = from_binary(&res).unwrap();
+        assert_eq!(value.extension.kind, OptionKind::Put);
+        assert_eq!(value.extension.underlying, underlying);
+        assert_eq!(value.extension.strike, strike);
+
+        for token_id in ["1", "2"] {
+            let msg = ExecuteMsg::TransferNft {
+                recipient: "holder".to_string(),
+                token_id: token_id.to_string(),
+            };
+            let _ = execute(deps.as_mut(), mock_env(), mock_info("writer", &[]), msg).unwrap();
+        }
+
+        // call holder buys the underlying at the strike
+        let info = mock_info("holder", &coins(40, "ETH"));
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Execute { id: 1 },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(BankMsg::Send {
+                    to_address: "writer".into(),
+                    amount: coins(40, "ETH"),
+                }),
+                SubMsg::new(BankMsg::Send {
+                    to_address: "holder".into(),
+                    amount: coins(1, "BTC"),
+                }),
+            ]
+        );
+
+        // put holder sells the underlying at the strike
+        let info = mock_info("holder", &coins(1, "BTC"));
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Execute { id: 2 },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(BankMsg::Send {
+                    to_address: "holder".into(),
+                    amount: coins(40, "ETH"),
+                }),
+                SubMsg::new(BankMsg::Send {
+                    to_address: "writer".into(),
+                    amount: coins(1, "BTC"),
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn partial_exercise() {
+        let mut deps = mock_dependencies();
+
+        let counter_offer = vec![coin(30, "ETH"), coin(300, "USD")];
+        let msg = InstantiateMsg {
+            option: Some(CreateOptionMsg {
+                underlying: Balance::from(coins(10, "BTC")),
+                strike: Balance::from(counter_offer),
+                expires: Expiration::AtHeight(100_000),
+                kind: None,
+            }),
+            name: None,
+            symbol: None,
+        };
+        let info = mock_info("creator", &coins(10, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // shares have to be proportional across the counter_offer
+        let info = mock_info("creator", &[coin(10, "ETH"), coin(200, "USD")]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Execute { id: 1 },
+        )
+        .unwrap_err();
+        match err {
+            ContractError::NotEqualCounterOffer { .. } => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // a third of the counter_offer releases a third of the collateral rounded down
+        let info = mock_info("creator", &[coin(10, "ETH"), coin(100, "USD")]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Execute { id: 1 },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(BankMsg::Send {
+                    to_address: "creator".into(),
+                    amount: vec![coin(10, "ETH"), coin(100, "USD")],
+                }),
+                SubMsg::new(BankMsg::Send {
+                    to_address: "creator".into(),
+                    amount: coins(3, "BTC"),
+                }),
+            ]
+        );
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Option { id: 1 }).unwrap();
+        let value: OptionResponse = from_binary(&res).unwrap();
+        assert_eq!(Balance::from(coins(7, "BTC")), value.collateral);
+        assert_eq!(
+            Balance::from(vec![coin(20, "ETH"), coin(200, "USD")]),
+            value.counter_offer
+        );
+
+        // the rest releases the remaining collateral and closes the option
+        let info = mock_info("creator", &[coin(20, "ETH"), coin(200, "USD")]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Execute { id: 1 },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[1],
+            SubMsg::new(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: coins(7, "BTC"),
+            })
+        );
+        query(deps.as_ref(), mock_env(), QueryMsg::Option { id: 1 }).unwrap_err();
+    }
+
+    #[test]
+    fn list_and_buy() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            option: Some(CreateOptionMsg {
+                underlying: Balance::from(coins(1, "BTC")),
+                strike: Balance::from(coins(40, "ETH")),
+                expires: Expiration::AtHeight(100_000),
+                kind: None,
+            }),
+            name: None,
+            symbol: None,
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // only the owner can list
+        let msg = ExecuteMsg::ListForSale {
+            id: 1,
+            price: coins(5, "USD"),
+        };
+        let info = mock_info("anyone", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // nothing to buy before listing
+        let info = mock_info("buyer", &coins(5, "USD"));
+        let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Buy { id: 1 }).unwrap_err();
+        match err {
+            ContractError::NotListed {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let info = mock_info("creator", &[]);
+        let _ = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // must pay the listed price
+        let info = mock_info("buyer", &coins(4, "USD"));
+        let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Buy { id: 1 }).unwrap_err();
+        match err {
+            ContractError::IncorrectPremium { expected, received } => {
+                assert_eq!(coins(5, "USD"), expected);
+                assert_eq!(coins(4, "USD"), received);
+            }
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // premium is forwarded to the seller and the buyer owns the option
+        let info = mock_info("buyer", &coins(5, "USD"));
+        let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Buy { id: 1 }).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: coins(5, "USD"),
+            })]
+        );
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Option { id: 1 }).unwrap();
+        let value: OptionResponse = from_binary(&res).unwrap();
+        assert_eq!("buyer", value.owner.as_str());
+        assert_eq!(None, value.price);
+
+        // listing can be cancelled and is dropped on transfer
+        let msg = ExecuteMsg::ListForSale {
+            id: 1,
+            price: coins(8, "USD"),
+        };
+        let info = mock_info("buyer", &[]);
+        let _ = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+        let _ = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::CancelListing { id: 1 },
+        )
+        .unwrap();
+        let info2 = mock_info("other", &coins(8, "USD"));
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info2.clone(),
+            ExecuteMsg::Buy { id: 1 },
+        )
+        .unwrap_err();
+        match err {
+            ContractError::NotListed {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let _ = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let transfer = ExecuteMsg::TransferNft {
+            recipient: "friend".into(),
+            token_id: "1".into(),
+        };
+        let _ = execute(deps.as_mut(), mock_env(), info, transfer).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), info2, ExecuteMsg::Buy { id: 1 }).unwrap_err();
+        match err {
+            ContractError::NotListed {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn unordered_funds() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            option: Some(CreateOptionMsg {
+                underlying: Balance::from(vec![coin(5, "BTC"), coin(0, "ATOM"), coin(2, "ALGO")]),
+                strike: Balance::from(vec![coin(300, "USD"), coin(30, "ETH")]),
+                expires: Expiration::AtHeight(100_000),
+                kind: None,
+            }),
+            name: None,
+            symbol: None,
+        };
+        let info = mock_info(
+            "creator",
+            &[coin(5, "BTC"), coin(0, "ATOM"), coin(2, "ALGO")],
+        );
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // collateral and counter_offer are stored sorted without zero amounts
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Option { id: 1 }).unwrap();
+        let value: OptionResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            Balance::from(vec![coin(2, "ALGO"), coin(5, "BTC")]),
+            value.collateral
+        );
+        assert_eq!(
+            Balance::from(vec![coin(30, "ETH"), coin(300, "USD")]),
+            value.counter_offer
+        );
+
+        // order, split denoms and zero amounts don't matter
+        let funds = vec![
+            coin(100, "USD"),
+            coin(0, "BTC"),
+            coin(30, "ETH"),
+            coin(200, "USD"),
+        ];
+        let info = mock_info("creator", &funds);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Execute { id: 1 },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0],
+            SubMsg::new(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: vec![coin(30, "ETH"), coin(300, "USD")],
+            })
+        );
+        query(deps.as_ref(), mock_env(), QueryMsg::Option { id: 1 }).unwrap_err();
+    }
+
+    #[test]
+    fn cancel() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            option: Some(CreateOptionMsg {
+                underlying: Balance::from(coins(1, "BTC")),
+                strike: Balance::from(coins(40, "ETH")),
+                expires: Expiration::AtHeight(100_000),
+                kind: None,
+            }),
+            name: None,
+            symbol: None,
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let msg = ExecuteMsg::CreateOption(CreateOptionMsg {
+            underlying: Balance::from(coins(3, "BTC")),
+            strike: Balance::from(coins(20, "ETH")),
+            expires: Expiration::AtHeight(100_000),
+            kind: None,
+        });
+        let info = mock_info("creator", &coins(3, "BTC"));
+        let _ = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // only the creator can cancel
+        let info = mock_info("anyone", &[]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Cancel { id: 1 },
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // not once the option was transferred
+        let transfer = ExecuteMsg::TransferNft {
+            recipient: "owner".into(),
+            token_id: "2".into(),
+        };
+        let info = mock_info("creator", &[]);
+        let _ = execute(deps.as_mut(), mock_env(), info.clone(), transfer).unwrap();
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::Cancel { id: 2 },
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // unsold option is closed before expiry and the collateral returned
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Cancel { id: 1 },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: coins(1, "BTC"),
+            })]
+        );
+        query(deps.as_ref(), mock_env(), QueryMsg::Option { id: 1 }).unwrap_err();
+    }
+
+    #[test]
+    fn add_collateral_and_update_counter_offer() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            option: Some(CreateOptionMsg {
+                underlying: Balance::from(coins(1, "BTC")),
+                strike: Balance::from(vec![coin(40, "ETH"), coin(100, "USD")]),
+                expires: Expiration::AtHeight(100_000),
+                kind: None,
+            }),
+            name: None,
+            symbol: None,
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let transfer = ExecuteMsg::TransferNft {
+            recipient: "owner".into(),
+            token_id: "1".into(),
+        };
+        let _ = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            transfer,
+        )
+        .unwrap();
+
+        // only the creator changes the terms
+        let info = mock_info("owner", &coins(1, "BTC"));
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::AddCollateral { id: 1 },
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // cw20 tokens cannot top up native collateral
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "creator".into(),
+            amount: Uint128::new(10),
+            msg: to_binary(&ReceiveMsg::AddCollateral { id: 1 }).unwrap(),
+        });
+        let err = execute(deps.as_mut(), mock_env(), mock_info("token", &[]), msg).unwrap_err();
+        match err {
+            ContractError::InvalidCollateral {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // nor can denoms the collateral doesn't hold
+        let info = mock_info("creator", &[coin(2, "BTC"), coin(5, "ATOM")]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::AddCollateral { id: 1 },
+        )
+        .unwrap_err();
+        match err {
+            ContractError::InvalidCollateral {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let info = mock_info("creator", &coins(2, "BTC"));
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::AddCollateral { id: 1 },
+        )
+        .unwrap();
+        assert_eq!(res.attributes[2], attr("added", "2BTC"));
+        assert_eq!(res.attributes[3], attr("collateral", "3BTC"));
+
+        // counter_offer cannot ask more or another asset
+        let msg = ExecuteMsg::UpdateCounterOffer {
+            id: 1,
+            counter_offer: Balance::from(vec![coin(40, "ETH"), coin(101, "USD")]),
+        };
+        let info = mock_info("creator", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        match err {
+            ContractError::CounterOfferNotLower {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+        let msg = ExecuteMsg::UpdateCounterOffer {
+            id: 1,
+            counter_offer: Balance::from(coins(1, "BTC")),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        match err {
+            ContractError::CounterOfferNotLower {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let msg = ExecuteMsg::UpdateCounterOffer {
+            id: 1,
+            counter_offer: Balance::from(coins(30, "ETH")),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.attributes[2], attr("previous", "40ETH,100USD"));
+        assert_eq!(res.attributes[3], attr("counter_offer", "30ETH"));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Option { id: 1 }).unwrap();
+        let value: OptionResponse = from_binary(&res).unwrap();
+        assert_eq!(Balance::from(coins(3, "BTC")), value.collateral);
+        assert_eq!(Balance::from(coins(30, "ETH")), value.counter_offer);
+
+        // no changes once expired
+        let mut env = mock_env();
+        env.block.height = 200_000;
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let err = execute(
+            deps.as_mut(),
+            env,
+            info,
+            ExecuteMsg::AddCollateral { id: 1 },
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Expired {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn partial_exercise_rounding() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            option: Some(CreateOptionMsg {
+                underlying: Balance::from(vec![coin(1, "ALGO"), coin(10, "BTC")]),
+                strike: Balance::from(coins(30, "ETH")),
+                expires: Expiration::AtHeight(100_000),
+                kind: None,
+            }),
+            name: None,
+            symbol: None,
+        };
+        let info = mock_info("creator", &[coin(1, "ALGO"), coin(10, "BTC")]);
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // denoms rounding down to zero are not sent
+        let info = mock_info("creator", &coins(10, "ETH"));
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Execute { id: 1 },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[1],
+            SubMsg::new(BankMsg::Send {
+                to_address: "creator".into(),
+                amount: coins(3, "BTC"),
+            })
+        );
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Option { id: 1 }).unwrap();
+        let value: OptionResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            Balance::from(vec![coin(1, "ALGO"), coin(7, "BTC")]),
+            value.collateral
+        );
+
+        // cw20 collateral that would release nothing rejects the exercise
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "writer".to_string(),
+            amount: Uint128::new(10),
+            msg: to_binary(&ReceiveMsg::CreateOption(CreateOptionMsg {
+                underlying: Balance::Cw20(Cw20CoinVerified {
+                    address: Addr::unchecked("gov"),
+                    amount: Uint128::new(10),
+                }),
+                strike: Balance::from(coins(300, "ETH")),
+                expires: Expiration::AtHeight(100_000),
+                kind: None,
+            }))
+            .unwrap(),
+        });
+        let _ = execute(deps.as_mut(), mock_env(), mock_info("gov", &[]), msg).unwrap();
+
+        let info = mock_info("writer", &coins(10, "ETH"));
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Execute { id: 2 },
+        )
+        .unwrap_err();
+        match err {
+            ContractError::NothingReleased {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let info = mock_info("writer", &coins(30, "ETH"));
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Execute { id: 2 },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[1],
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "gov".into(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "writer".into(),
+                    amount: Uint128::new(1),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn cw20_terms_bounds() {
+        let mut deps = mock_dependencies();
+
+        let usd = |amount| {
+            Balance::Cw20(Cw20CoinVerified {
+                address: Addr::unchecked("usd"),
+                amount: Uint128::new(amount),
+            })
+        };
+        let msg = InstantiateMsg {
+            option: Some(CreateOptionMsg {
+                underlying: Balance::from(coins(1, "BTC")),
+                strike: usd(0),
+                expires: Expiration::AtHeight(100_000),
+                kind: None,
+            }),
+            name: None,
+            symbol: None,
+        };
+        let info = mock_info("creator", &coins(1, "BTC"));
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        match err {
+            ContractError::ZeroCw20Amount {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let msg = InstantiateMsg {
+            option: Some(CreateOptionMsg {
+                underlying: Balance::from(coins(1, "BTC")),
+                strike: usd(500),
+                expires: Expiration::AtHeight(100_000),
+                kind: None,
+            }),
+            name: None,
+            symbol: None,
+        };
+        let _ = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // lowering a cw20 counter_offer to zero would make the option unexercisable
+        let msg = ExecuteMsg::UpdateCounterOffer {
+            id: 1,
+            counter_offer: usd(0),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+        match err {
+            ContractError::ZeroCw20Amount {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // collateral top ups cannot overflow
+        let create = |amount, msg| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "writer".to_string(),
+                amount: Uint128::new(amount),
+                msg: to_binary(&msg).unwrap(),
+            })
+        };
+        let msg = create(
+            u128::MAX,
+            ReceiveMsg::CreateOption(CreateOptionMsg {
+                underlying: Balance::Cw20(Cw20CoinVerified {
+                    address: Addr::unchecked("gov"),
+                    amount: Uint128::MAX,
+                }),
+                strike: usd(500),
+                expires: Expiration::AtHeight(100_000),
+                kind: None,
+            }),
+        );
+        let _ = execute(deps.as_mut(), mock_env(), mock_info("gov", &[]), msg).unwrap();
+        let msg = create(1, ReceiveMsg::AddCollateral { id: 2 });
+        let err = execute(deps.as_mut(), mock_env(), mock_info("gov", &[]), msg).unwrap_err();
+        match err {
+            ContractError::Std(StdError::Overflow { .. }) => {}
+            e => panic!("unexpected error: {}", e),
+        }
     }
 }
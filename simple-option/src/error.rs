@@ -1,4 +1,6 @@
 use cosmwasm_std::{Coin, StdError};
+use cw20::Balance;
+use cw_utils::PaymentError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,24 +8,67 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
     #[error("Cannot create expired option")]
     CreateExpired {},
 
+    #[error("Option must expire at a height or time")]
+    NeverExpires {},
+
+    #[error("Cw20 amount must not be zero")]
+    ZeroCw20Amount {},
+
     #[error("Option expired")]
     Expired {},
 
     #[error("Option not expired")]
     NotExpired {},
 
-    #[error("Must send exact counter_offer: {val:?}")]
-    NotEqualCounterOffer { val: Vec<Coin> },
+    #[error("Must lock the {side} of the option: expected {expected:?}, received {received:?}")]
+    WrongCollateral {
+        side: String,
+        expected: Balance,
+        received: Balance,
+    },
+
+    #[error("Must send exact counter_offer: expected {expected:?}, received {received:?}")]
+    NotEqualCounterOffer {
+        expected: Balance,
+        received: Balance,
+    },
+
+    #[error("Exercised share is too small to release any collateral")]
+    NothingReleased {},
 
     #[error("Don't send funds when burn")]
     BurnFunds {},
 
+    #[error("Cannot set approval that is already expired")]
+    ApprovalExpired {},
+
+    #[error("Added collateral must be non-empty and of the collateral asset")]
+    InvalidCollateral {},
+
+    #[error("New counter_offer must not ask more of any asset")]
+    CounterOfferNotLower {},
+
+    #[error("Price must not be empty or zero")]
+    InvalidPrice {},
+
+    #[error("Option is not for sale")]
+    NotListed {},
+
+    #[error("Must send exact price: expected {expected:?}, received {received:?}")]
+    IncorrectPremium {
+        expected: Vec<Coin>,
+        received: Vec<Coin>,
+    },
+
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
     // Add any other custom errors you like here.
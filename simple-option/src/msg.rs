@@ -1,32 +1,180 @@
-use cosmwasm_std::Coin;
+use cosmwasm_std::{Addr, Binary, Coin};
+use cw20::{Balance, Cw20ReceiveMsg};
+use cw721::NftInfoResponse;
+use cw_utils::Expiration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::state::State;
+use crate::state::{OptionKind, State};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
+    // creates the first option with id 1 if set
     // owner and creator come from env
-    // collateral comes from env
-    pub counter_offer: Vec<Coin>,
-    pub expires: u64,
+    // collateral comes from env and must be the locked side of the terms
+    /// Terms of an option to write on instantiation, the contract starts empty if unset
+    pub option: Option<CreateOptionMsg>,
+    /// cw721 collection name, defaults to "Simple Option"
+    pub name: Option<String>,
+    /// cw721 collection symbol, defaults to "OPTION"
+    pub symbol: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    /// Owner can transfer to a new owner
-    Transfer { recipient: String },
-    /// Owner can post counter_offer on unexpired option to execute and get collateral
-    Execute {},
+    /// Create another option, funds must be the underlying of a call or the strike of a put
+    CreateOption(CreateOptionMsg),
+    /// Create or exercise options with cw20 tokens, see `ReceiveMsg`
+    Receive(Cw20ReceiveMsg),
+    /// Owner, approved spender or operator can transfer to a new owner,
+    /// token_id is the option id
+    TransferNft {
+        recipient: String,
+        token_id: String,
+    },
+    /// Transfer to a contract and notify it with `Cw721ReceiveMsg`
+    SendNft {
+        contract: String,
+        token_id: String,
+        msg: Binary,
+    },
+    /// Allow spender to transfer a single option
+    Approve {
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    Revoke {
+        spender: String,
+        token_id: String,
+    },
+    /// Allow operator to transfer all options of the sender
+    ApproveAll {
+        operator: String,
+        expires: Option<Expiration>,
+    },
+    RevokeAll {
+        operator: String,
+    },
+    /// Owner can post counter_offer on unexpired option to execute and get collateral,
+    /// a proportional share of it releases the same share of collateral rounded down
+    Execute {
+        id: u64,
+    },
     /// Burn will release collateral if expired
-    Burn {},
+    Burn {
+        id: u64,
+    },
+    /// Creator locks the sent funds as additional collateral of a live option,
+    /// only denoms the collateral already holds are accepted
+    AddCollateral {
+        id: u64,
+    },
+    /// Creator lowers the counter_offer of a live option, amounts can only go down
+    UpdateCounterOffer {
+        id: u64,
+        counter_offer: Balance,
+    },
+    /// Creator still owning the option closes it and takes back the collateral
+    Cancel {
+        id: u64,
+    },
+    /// Owner offers an unexpired option for sale at the price in native coins
+    ListForSale {
+        id: u64,
+        price: Vec<Coin>,
+    },
+    /// Owner takes the option off sale
+    CancelListing {
+        id: u64,
+    },
+    /// Pay the listed price to become owner, the premium goes to the seller
+    Buy {
+        id: u64,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CreateOptionMsg {
+    /// Asset the option is written on, locked by the writer of a call
+    pub underlying: Balance,
+    /// Price of the underlying, locked by the writer of a put
+    pub strike: Balance,
+    pub expires: Expiration,
+    /// Defaults to call
+    pub kind: Option<OptionKind>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    /// Create an option with the sent tokens as collateral
+    CreateOption(CreateOptionMsg),
+    /// Owner exercises an option whose counter_offer is the sent tokens
+    Execute { id: u64 },
+    /// Creator adds the sent tokens to the collateral
+    AddCollateral { id: u64 },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
-    Config {},
+    Option {
+        id: u64,
+    },
+    // cw721 queries, token ids are option ids
+    OwnerOf {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    Approval {
+        token_id: String,
+        spender: String,
+        include_expired: Option<bool>,
+    },
+    Approvals {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    AllOperators {
+        owner: String,
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    NumTokens {},
+    ContractInfo {},
+    NftInfo {
+        token_id: String,
+    },
+    AllNftInfo {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    Tokens {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    AllTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+pub type OptionResponse = State;
+
+/// Terms of an option, exposed as cw721 token metadata
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OptionExtension {
+    pub creator: Addr,
+    pub kind: OptionKind,
+    pub underlying: Balance,
+    pub strike: Balance,
+    pub collateral: Balance,
+    pub counter_offer: Balance,
+    pub expires: Expiration,
 }
 
-pub type ConfigResponse = State;
+pub type OptionNftInfoResponse = NftInfoResponse<OptionExtension>;
@@ -1,16 +1,131 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Coin};
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, BlockInfo, Coin, StdResult, Storage};
+use cw20::Balance;
+use cw721::ContractInfoResponse;
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+use cw_utils::Expiration;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct State {
     pub creator: Addr,
     pub owner: Addr,
-    pub collateral: Vec<Coin>,
-    pub counter_offer: Vec<Coin>,
-    pub expires: u64,
+    pub kind: OptionKind,
+    /// Native coins or cw20 tokens locked by the creator, the underlying of a call
+    /// or the strike of a put
+    pub collateral: Balance,
+    /// Native coins or cw20 tokens the owner pays to exercise, the strike of a call
+    /// or the underlying of a put
+    pub counter_offer: Balance,
+    pub expires: Expiration,
+    /// Addresses allowed to transfer this option, cleared on transfer
+    pub approvals: Vec<Approval>,
+    /// Premium the owner asks to sell the option for, cleared on transfer
+    pub price: Option<Vec<Coin>>,
 }
 
-pub const STATE: Item<State> = Item::new("state");
+/// The kind decides which side of the terms the writer locks and which way the
+/// underlying moves on exercise
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OptionKind {
+    /// Right to buy: the writer locks the underlying, the owner pays the strike
+    Call,
+    /// Right to sell: the writer locks the strike, the owner delivers the underlying
+    Put,
+}
+
+impl OptionKind {
+    pub fn as_str(&self) -> &str {
+        match self {
+            OptionKind::Call => "call",
+            OptionKind::Put => "put",
+        }
+    }
+
+    /// Name of the side of the terms the writer locks
+    pub fn locked(&self) -> &str {
+        match self {
+            OptionKind::Call => "underlying",
+            OptionKind::Put => "strike",
+        }
+    }
+
+    /// Orders underlying and strike as (collateral, counter_offer), and back
+    pub fn orient<T>(&self, a: T, b: T) -> (T, T) {
+        match self {
+            OptionKind::Call => (a, b),
+            OptionKind::Put => (b, a),
+        }
+    }
+}
+
+impl State {
+    /// Asset the option is written on
+    pub fn underlying(&self) -> &Balance {
+        self.kind.orient(&self.collateral, &self.counter_offer).0
+    }
+
+    /// Price of the underlying
+    pub fn strike(&self) -> &Balance {
+        self.kind.orient(&self.collateral, &self.counter_offer).1
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Approval {
+    pub spender: Addr,
+    pub expires: Expiration,
+}
+
+impl Approval {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        self.expires.is_expired(block)
+    }
+}
+
+/// Saves a new option under the next id and returns the id
+pub fn save_option(storage: &mut dyn Storage, state: &State) -> StdResult<u64> {
+    let id = OPTION_SEQ.may_load(storage)?.unwrap_or_default() + 1;
+    OPTION_SEQ.save(storage, &id)?;
+    options().save(storage, id, state)?;
+    let count = NUM_TOKENS.may_load(storage)?.unwrap_or_default();
+    NUM_TOKENS.save(storage, &(count + 1))?;
+    Ok(id)
+}
+
+/// Deletes an option once it is settled, burned or cancelled
+pub fn remove_option(storage: &mut dyn Storage, id: u64) -> StdResult<()> {
+    options().remove(storage, id)?;
+    NUM_TOKENS.update(storage, |count| -> StdResult<_> { Ok(count - 1) })?;
+    Ok(())
+}
+
+/// cw721 collection name and symbol
+pub const CONTRACT_INFO: Item<ContractInfoResponse> = Item::new("contract_info");
+/// Last option id, ids start at 1
+pub const OPTION_SEQ: Item<u64> = Item::new("option_seq");
+/// Number of live options, served by the cw721 num_tokens query
+pub const NUM_TOKENS: Item<u64> = Item::new("num_tokens");
+
+pub struct OptionIndexes<'a> {
+    pub owner: MultiIndex<'a, Addr, State, u64>,
+}
+
+impl<'a> IndexList<State> for OptionIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<State>> + '_> {
+        let v: Vec<&dyn Index<State>> = vec![&self.owner];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Options by id, indexed by owner to page the options of an owner
+pub fn options<'a>() -> IndexedMap<'a, u64, State, OptionIndexes<'a>> {
+    let indexes = OptionIndexes {
+        owner: MultiIndex::new(|s| s.owner.clone(), "options", "options__owner"),
+    };
+    IndexedMap::new("options", indexes)
+}
+/// Operators approved for all options of an owner, keyed by (owner, operator)
+pub const OPERATORS: Map<(&Addr, &Addr), Expiration> = Map::new("operators");